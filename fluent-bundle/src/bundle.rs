@@ -10,6 +10,7 @@ use std::borrow::Cow;
 use std::collections::hash_map::Entry as HashEntry;
 use std::default::Default;
 use std::fmt;
+use std::io;
 
 use fluent_syntax::ast;
 use intl_memoizer::IntlLangMemoizer;
@@ -24,6 +25,7 @@ use crate::message::FluentMessage;
 use crate::resolver::{ResolveValue, Scope, WriteValue};
 use crate::resource::FluentResource;
 use crate::types::FluentValue;
+use crate::writer::IoWriter;
 
 /// A collection of localization messages for a single locale, which are meant
 /// to be used together in a single view, widget or any other UI abstraction.
@@ -130,15 +132,139 @@ use crate::types::FluentValue;
 /// As you may have noticed, [`fluent_bundle::FluentBundle`](crate::FluentBundle) is a specialization of [`fluent_bundle::bundle::FluentBundle`](crate::bundle::FluentBundle)
 /// which works with an [`IntlLangMemoizer`] over [`RefCell`](std::cell::RefCell).
 /// In scenarios where the memoizer must work concurrently, there's an implementation of
-/// [`IntlLangMemoizer`](intl_memoizer::concurrent::IntlLangMemoizer) that uses [`Mutex`](std::sync::Mutex) and there's [`FluentBundle::new_concurrent`] which works with that.
+/// [`IntlLangMemoizer`](intl_memoizer::concurrent::IntlLangMemoizer) that uses [`RwLock`](std::sync::RwLock) and there's [`FluentBundle::new_concurrent`] which works with that.
+///
+/// The resulting [`fluent_bundle::concurrent::FluentBundle`](crate::concurrent::FluentBundle) is [`Send`] and [`Sync`],
+/// so it can be shared between threads behind an [`Arc`](std::sync::Arc), together with
+/// resources stored as [`Arc<FluentResource>`](std::sync::Arc).
 pub struct FluentBundle<R, M> {
     pub locales: Vec<LanguageIdentifier>,
     pub(crate) resources: Vec<R>,
     pub(crate) entries: FxHashMap<String, Entry>,
     pub(crate) intls: M,
-    pub(crate) use_isolating: bool,
+    pub(crate) isolation_strategy: IsolationStrategy,
     pub(crate) transform: Option<fn(&str) -> Cow<str>>,
     pub(crate) formatter: Option<fn(&FluentValue, &M) -> Option<String>>,
+    pub(crate) error_rendering: ErrorRendering,
+}
+
+/// Controls how [`FluentBundle`] marks the direction of placeables
+/// in formatted patterns.
+///
+/// Placeables such as variables may be of a different direction than
+/// the surrounding message, for example a right-to-left user name
+/// presented in a left-to-right message. Without marking, the neutral
+/// characters around the placeable may be laid out incorrectly.
+///
+/// Isolation is only applied to patterns with more than one element.
+///
+/// # Example
+///
+/// ```
+/// use fluent_bundle::{FluentArgs, FluentBundle, FluentResource};
+/// use fluent_bundle::bundle::IsolationStrategy;
+/// use unic_langid::langid;
+///
+/// let ftl_string = String::from("intro = Welcome, { $name }.");
+/// let resource = FluentResource::try_new(ftl_string)
+///     .expect("Could not parse an FTL string.");
+///
+/// let mut bundle = FluentBundle::new(vec![langid!("en-US")]);
+/// bundle.add_resource(&resource)
+///     .expect("Failed to add FTL resources to the bundle.");
+/// bundle.set_isolation_strategy(IsolationStrategy::DirectionAware);
+///
+/// let msg = bundle.get_message("intro").expect("Message doesn't exist.");
+/// let pattern = msg.value().expect("Message has no value.");
+/// let mut errors = vec![];
+///
+/// let mut args = FluentArgs::new();
+/// args.set("name", "John");
+/// assert_eq!(
+///     bundle.format_pattern(&pattern, Some(&args), &mut errors),
+///     "Welcome, John."
+/// );
+///
+/// let mut args = FluentArgs::new();
+/// args.set("name", "שלום");
+/// assert_eq!(
+///     bundle.format_pattern(&pattern, Some(&args), &mut errors),
+///     "Welcome, \u{2068}שלום\u{2069}."
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IsolationStrategy {
+    /// Placeables are not marked.
+    None,
+    /// Every placeable is wrapped in First Strong Isolate (U+2068)
+    /// and Pop Directional Isolate (U+2069) marks.
+    ///
+    /// This is the default.
+    #[default]
+    Isolate,
+    /// Every placeable is surrounded by a Left-To-Right Mark (U+200E)
+    /// or a Right-To-Left Mark (U+200F), depending on the direction
+    /// of the bundle locale.
+    ///
+    /// This is useful for environments which do not support
+    /// the isolation marks, such as some terminals or legacy controls.
+    Marks,
+    /// A placeable is wrapped in isolation marks only if its
+    /// direction, as determined by its first strong character,
+    /// differs from the direction of the bundle locale.
+    DirectionAware,
+}
+
+/// Controls how [`FluentBundle`] renders placeables which could not be
+/// resolved, such as references to missing variables, messages, terms
+/// or attributes.
+///
+/// The rendering only affects the formatted output. The errors are
+/// reported in the same way regardless of the selected variant.
+///
+/// # Example
+///
+/// ```
+/// use fluent_bundle::{FluentBundle, FluentResource};
+/// use fluent_bundle::bundle::ErrorRendering;
+/// use unic_langid::langid;
+///
+/// let ftl_string = String::from("intro = Welcome, { $name }.");
+/// let resource = FluentResource::try_new(ftl_string)
+///     .expect("Could not parse an FTL string.");
+///
+/// let mut bundle = FluentBundle::new(vec![langid!("en-US")]);
+/// bundle.add_resource(&resource)
+///     .expect("Failed to add FTL resources to the bundle.");
+/// bundle.set_use_isolating(false);
+/// bundle.set_error_rendering(ErrorRendering::Placeholder("…"));
+///
+/// let msg = bundle.get_message("intro").expect("Message doesn't exist.");
+/// let pattern = msg.value().expect("Message has no value.");
+///
+/// let mut errors = vec![];
+/// assert_eq!(
+///     bundle.format_pattern(&pattern, None, &mut errors),
+///     "Welcome, …."
+/// );
+/// assert_eq!(errors.len(), 1);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub enum ErrorRendering {
+    /// Renders the source of the expression wrapped in braces,
+    /// for example `{$name}` or `{msg-id}`.
+    ///
+    /// This is the default.
+    #[default]
+    Source,
+    /// Renders nothing.
+    Empty,
+    /// Renders the given placeholder in place of the expression.
+    Placeholder(&'static str),
+    /// Calls the function with the source of the expression,
+    /// for example `$name` or `msg-id`, and lets it write
+    /// the rendering.
+    Custom(fn(&mut dyn fmt::Write, &str) -> fmt::Result),
 }
 
 impl<R, M> FluentBundle<R, M> {
@@ -326,8 +452,24 @@ impl<R, M> FluentBundle<R, M> {
     ///
     /// In some cases, such as testing, the user may want
     /// to disable the isolating.
+    ///
+    /// This is a shorthand for setting [`IsolationStrategy::Isolate`]
+    /// or [`IsolationStrategy::None`] with
+    /// [`set_isolation_strategy`](FluentBundle::set_isolation_strategy).
     pub fn set_use_isolating(&mut self, value: bool) {
-        self.use_isolating = value;
+        self.isolation_strategy = if value {
+            IsolationStrategy::Isolate
+        } else {
+            IsolationStrategy::None
+        };
+    }
+
+    /// This method allows to specify how the direction of
+    /// placeables is marked in formatted patterns.
+    ///
+    /// See [`IsolationStrategy`] for the available options.
+    pub fn set_isolation_strategy(&mut self, strategy: IsolationStrategy) {
+        self.isolation_strategy = strategy;
     }
 
     /// This method allows to specify a function that will
@@ -351,6 +493,19 @@ impl<R, M> FluentBundle<R, M> {
         self.formatter = func;
     }
 
+    /// This method allows to specify how placeables which
+    /// failed to resolve are rendered in the formatted output.
+    ///
+    /// By default, the source of the failed expression is rendered
+    /// in braces, which makes the errors easy to spot during
+    /// development. In production, one may prefer to render
+    /// an empty string or a neutral placeholder instead.
+    ///
+    /// See [`ErrorRendering`] for the available options.
+    pub fn set_error_rendering(&mut self, rendering: ErrorRendering) {
+        self.error_rendering = rendering;
+    }
+
     /// Returns true if this bundle contains a message with the given id.
     ///
     /// # Examples
@@ -451,6 +606,62 @@ impl<R, M> FluentBundle<R, M> {
         pattern.write(w, &mut scope)
     }
 
+    /// Writes a formatted pattern which comes from a `FluentMessage`
+    /// into an [`io::Write`] sink.
+    ///
+    /// This is a counterpart of [`write_pattern`](FluentBundle::write_pattern)
+    /// which allows streaming the output directly into files, sockets or
+    /// standard output without an intermediate `String`.
+    ///
+    /// Unlike [`fmt::Error`], the returned [`io::Error`] preserves the
+    /// reason why the underlying sink failed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fluent_bundle::{FluentBundle, FluentResource};
+    /// use unic_langid::langid;
+    ///
+    /// let ftl_string = String::from("hello-world = Hello World!");
+    /// let resource = FluentResource::try_new(ftl_string)
+    ///     .expect("Failed to parse an FTL string.");
+    ///
+    /// let langid_en = langid!("en-US");
+    /// let mut bundle = FluentBundle::new(vec![langid_en]);
+    ///
+    /// bundle.add_resource(&resource)
+    ///     .expect("Failed to add FTL resources to the bundle.");
+    ///
+    /// let msg = bundle.get_message("hello-world")
+    ///     .expect("Failed to retrieve a FluentMessage.");
+    ///
+    /// let pattern = msg.value()
+    ///     .expect("Missing Value.");
+    /// let mut errors = vec![];
+    ///
+    /// let mut output: Vec<u8> = vec![];
+    /// bundle.write_pattern_io(&mut output, &pattern, None, &mut errors)
+    ///     .expect("Failed to write.");
+    ///
+    /// assert_eq!(output, b"Hello World!");
+    /// ```
+    pub fn write_pattern_io<'bundle, W>(
+        &'bundle self,
+        w: &mut W,
+        pattern: &'bundle ast::Pattern<&str>,
+        args: Option<&'bundle FluentArgs>,
+        errors: &mut Vec<FluentError>,
+    ) -> io::Result<()>
+    where
+        R: Borrow<FluentResource>,
+        W: io::Write,
+        M: MemoizerKind,
+    {
+        let mut writer = IoWriter::new(w);
+        let result = self.write_pattern(&mut writer, pattern, args, errors);
+        writer.into_result(result)
+    }
+
     /// Formats a pattern which comes from a `FluentMessage`.
     ///
     /// # Example
@@ -581,9 +792,10 @@ impl<R> FluentBundle<R, IntlLangMemoizer> {
             resources: vec![],
             entries: FxHashMap::default(),
             intls: IntlLangMemoizer::new(first_locale),
-            use_isolating: true,
+            isolation_strategy: IsolationStrategy::default(),
             transform: None,
             formatter: None,
+            error_rendering: ErrorRendering::default(),
         }
     }
 }
@@ -1,14 +1,71 @@
+//! Thread-safe specialization of [`FluentBundle`](crate::bundle::FluentBundle).
+//!
+//! [`FluentBundle`] in this module uses the concurrent [`IntlLangMemoizer`],
+//! which makes the bundle [`Send`] and [`Sync`] as long as its resources are.
+//! This allows a single bundle to be stored behind an [`Arc`](std::sync::Arc)
+//! and used for formatting from many threads.
+//!
+//! # Example
+//!
+//! ```
+//! use fluent_bundle::concurrent::FluentBundle;
+//! use fluent_bundle::{FluentArgs, FluentResource};
+//! use std::sync::Arc;
+//! use std::thread;
+//! use unic_langid::langid;
+//!
+//! let ftl_string = String::from("emails = { $count ->
+//!     [one] You have one email.
+//!    *[other] You have { $count } emails.
+//! }");
+//! let resource = Arc::new(
+//!     FluentResource::try_new(ftl_string).expect("Could not parse an FTL string."),
+//! );
+//!
+//! let mut bundle = FluentBundle::new_concurrent(vec![langid!("en-US")]);
+//! bundle.set_use_isolating(false);
+//! bundle
+//!     .add_resource(Arc::clone(&resource))
+//!     .expect("Failed to add FTL resources to the bundle.");
+//! let bundle = Arc::new(bundle);
+//!
+//! let handles: Vec<_> = (1..=4)
+//!     .map(|count| {
+//!         let bundle = Arc::clone(&bundle);
+//!         thread::spawn(move || {
+//!             let msg = bundle.get_message("emails").expect("Message doesn't exist.");
+//!             let pattern = msg.value().expect("Message has no value.");
+//!             let mut args = FluentArgs::new();
+//!             args.set("count", count);
+//!             let mut errors = vec![];
+//!             bundle.format_pattern(pattern, Some(&args), &mut errors).to_string()
+//!         })
+//!     })
+//!     .collect();
+//!
+//! let results: Vec<String> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+//! assert_eq!(results[0], "You have one email.");
+//! assert_eq!(results[3], "You have 4 emails.");
+//! ```
 use intl_memoizer::{concurrent::IntlLangMemoizer, Memoizable};
 use rustc_hash::FxHashMap;
 use unic_langid::LanguageIdentifier;
 
-use crate::bundle::FluentBundle;
+use crate::bundle::{ErrorRendering, IsolationStrategy};
 use crate::memoizer::MemoizerKind;
 use crate::types::FluentType;
 
-impl<R> FluentBundle<R, IntlLangMemoizer> {
-    /// A constructor analogous to [`FluentBundle::new`] but operating
-    /// on a concurrent version of [`IntlLangMemoizer`] over [`Mutex`](std::sync::Mutex).
+/// Specialized [`FluentBundle`](crate::bundle::FluentBundle) over
+/// concurrent [`IntlLangMemoizer`](intl_memoizer::concurrent::IntlLangMemoizer).
+///
+/// This is the thread-safe variant of the [`FluentBundle`](crate::FluentBundle),
+/// which can be constructed with
+/// [`FluentBundle::new_concurrent`](crate::bundle::FluentBundle::new_concurrent).
+pub type FluentBundle<R> = crate::bundle::FluentBundle<R, IntlLangMemoizer>;
+
+impl<R> FluentBundle<R> {
+    /// A constructor analogous to [`FluentBundle::new`](crate::FluentBundle::new) but operating
+    /// on a concurrent version of [`IntlLangMemoizer`] over [`RwLock`](std::sync::RwLock).
     ///
     /// # Example
     ///
@@ -21,6 +78,18 @@ impl<R> FluentBundle<R, IntlLangMemoizer> {
     /// let mut bundle: FluentBundle<FluentResource, _> =
     ///     FluentBundle::new_concurrent(vec![langid_en]);
     /// ```
+    ///
+    /// or, using the type alias from this module:
+    ///
+    /// ```
+    /// use fluent_bundle::concurrent::FluentBundle;
+    /// use fluent_bundle::FluentResource;
+    /// use unic_langid::langid;
+    ///
+    /// let langid_en = langid!("en-US");
+    /// let mut bundle: FluentBundle<FluentResource> =
+    ///     FluentBundle::new_concurrent(vec![langid_en]);
+    /// ```
     pub fn new_concurrent(locales: Vec<LanguageIdentifier>) -> Self {
         let first_locale = locales.get(0).cloned().unwrap_or_default();
         Self {
@@ -28,9 +97,10 @@ impl<R> FluentBundle<R, IntlLangMemoizer> {
             resources: vec![],
             entries: FxHashMap::default(),
             intls: IntlLangMemoizer::new(first_locale),
-            use_isolating: true,
+            isolation_strategy: IsolationStrategy::default(),
             transform: None,
             formatter: None,
+            error_rendering: ErrorRendering::default(),
         }
     }
 }
@@ -57,3 +127,12 @@ impl MemoizerKind for IntlLangMemoizer {
         value.as_string_threadsafe(self)
     }
 }
+
+// Compile-time assertions that the concurrent bundle can be shared across threads.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<FluentBundle<crate::FluentResource>>();
+    assert_send_sync::<FluentBundle<&crate::FluentResource>>();
+    assert_send_sync::<FluentBundle<std::sync::Arc<crate::FluentResource>>>();
+};
@@ -100,7 +100,7 @@
 //! matures and higher level APIs are being developed.
 mod args;
 pub mod bundle;
-mod concurrent;
+pub mod concurrent;
 mod entry;
 mod errors;
 #[doc(hidden)]
@@ -110,6 +110,7 @@ mod message;
 pub mod resolver;
 mod resource;
 pub mod types;
+mod writer;
 
 pub use args::FluentArgs;
 /// Specialized [`FluentBundle`](crate::bundle::FluentBundle) over
@@ -117,7 +118,8 @@ pub use args::FluentArgs;
 ///
 /// This is the basic variant of the [`FluentBundle`](crate::bundle::FluentBundle).
 ///
-/// The concurrent specialization, can be constructed with
+/// The concurrent specialization is available as
+/// [`concurrent::FluentBundle`](crate::concurrent::FluentBundle) and can be constructed with
 /// [`FluentBundle::new_concurrent`](crate::bundle::FluentBundle::new_concurrent).
 pub type FluentBundle<R> = bundle::FluentBundle<R, intl_memoizer::IntlLangMemoizer>;
 pub use errors::FluentError;
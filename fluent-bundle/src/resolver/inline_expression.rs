@@ -44,9 +44,7 @@ impl<'p> WriteValue for ast::InlineExpression<&'p str> {
                             .map(|value| scope.track(w, value, self))
                             .unwrap_or_else(|| {
                                 scope.add_error(ResolverError::NoValue(id.name.to_string()));
-                                w.write_char('{')?;
-                                self.write_error(w)?;
-                                w.write_char('}')
+                                scope.write_error_placeholder(w, self)
                             })
                     }
                 } else {
@@ -91,7 +89,7 @@ impl<'p> WriteValue for ast::InlineExpression<&'p str> {
                 if let Some(func) = func {
                     let result = func(resolved_positional_args.as_slice(), &resolved_named_args);
                     if let FluentValue::Error = result {
-                        self.write_error(w)
+                        scope.write_error_placeholder(w, self)
                     } else {
                         w.write_str(&result.as_string(scope))
                     }
@@ -108,9 +106,7 @@ impl<'p> WriteValue for ast::InlineExpression<&'p str> {
                     if scope.local_args.is_none() {
                         scope.add_error(self.into());
                     }
-                    w.write_char('{')?;
-                    self.write_error(w)?;
-                    w.write_char('}')
+                    scope.write_error_placeholder(w, self)
                 }
             }
             Self::Placeable { expression } => expression.write(w, scope),
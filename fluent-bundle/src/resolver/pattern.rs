@@ -5,7 +5,10 @@ use std::borrow::Borrow;
 use std::fmt;
 
 use fluent_syntax::ast;
+use unic_langid::CharacterDirection;
+use unicode_bidi::{bidi_class, BidiClass};
 
+use crate::bundle::IsolationStrategy;
 use crate::memoizer::MemoizerKind;
 use crate::resolver::ResolveValue;
 use crate::resource::FluentResource;
@@ -47,7 +50,8 @@ impl<'p> WriteValue for ast::Pattern<&'p str> {
                         return Ok(());
                     }
 
-                    let needs_isolation = scope.bundle.use_isolating
+                    let needs_isolation = scope.bundle.isolation_strategy
+                        != IsolationStrategy::None
                         && len > 1
                         && !matches!(
                             expression,
@@ -60,11 +64,9 @@ impl<'p> WriteValue for ast::Pattern<&'p str> {
                                 )
                         );
                     if needs_isolation {
-                        w.write_char('\u{2068}')?;
-                    }
-                    scope.maybe_track(w, self, expression)?;
-                    if needs_isolation {
-                        w.write_char('\u{2069}')?;
+                        write_isolated(w, scope, self, expression)?;
+                    } else {
+                        scope.maybe_track(w, self, expression)?;
                     }
                 }
             }
@@ -80,6 +82,70 @@ impl<'p> WriteValue for ast::Pattern<&'p str> {
     }
 }
 
+fn write_isolated<'scope, 'errors, W, R, M>(
+    w: &mut W,
+    scope: &mut Scope<'scope, 'errors, R, M>,
+    pattern: &'scope ast::Pattern<&str>,
+    expression: &'scope ast::Expression<&str>,
+) -> fmt::Result
+where
+    W: fmt::Write,
+    R: Borrow<FluentResource>,
+    M: MemoizerKind,
+{
+    match scope.bundle.isolation_strategy {
+        IsolationStrategy::None => scope.maybe_track(w, pattern, expression),
+        IsolationStrategy::Isolate => {
+            w.write_char('\u{2068}')?;
+            scope.maybe_track(w, pattern, expression)?;
+            w.write_char('\u{2069}')
+        }
+        IsolationStrategy::Marks => {
+            let mark = match locale_direction(scope) {
+                CharacterDirection::RTL => '\u{200F}',
+                _ => '\u{200E}',
+            };
+            w.write_char(mark)?;
+            scope.maybe_track(w, pattern, expression)?;
+            w.write_char(mark)
+        }
+        IsolationStrategy::DirectionAware => {
+            let mut value = String::new();
+            scope.maybe_track(&mut value, pattern, expression)?;
+            match text_direction(&value) {
+                Some(direction) if direction != locale_direction(scope) => {
+                    w.write_char('\u{2068}')?;
+                    w.write_str(&value)?;
+                    w.write_char('\u{2069}')
+                }
+                _ => w.write_str(&value),
+            }
+        }
+    }
+}
+
+fn locale_direction<R, M>(scope: &Scope<R, M>) -> CharacterDirection {
+    match scope
+        .bundle
+        .locales
+        .first()
+        .map(|l| l.character_direction())
+    {
+        Some(CharacterDirection::RTL) => CharacterDirection::RTL,
+        _ => CharacterDirection::LTR,
+    }
+}
+
+// Returns the direction of the first strong character of the text,
+// or `None` if the text has no strong characters, e.g. is a number.
+fn text_direction(text: &str) -> Option<CharacterDirection> {
+    text.chars().find_map(|ch| match bidi_class(ch) {
+        BidiClass::L => Some(CharacterDirection::LTR),
+        BidiClass::R | BidiClass::AL => Some(CharacterDirection::RTL),
+        _ => None,
+    })
+}
+
 impl<'p> ResolveValue for ast::Pattern<&'p str> {
     fn resolve<'source, 'errors, R, M>(
         &'source self,
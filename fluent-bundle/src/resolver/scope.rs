@@ -1,4 +1,4 @@
-use crate::bundle::FluentBundle;
+use crate::bundle::{ErrorRendering, FluentBundle};
 use crate::memoizer::MemoizerKind;
 use crate::resolver::{ResolveValue, ResolverError, WriteValue};
 use crate::types::FluentValue;
@@ -71,9 +71,7 @@ impl<'scope, 'errors, R, M> Scope<'scope, 'errors, R, M> {
         }
         exp.write(w, self)?;
         if self.dirty {
-            w.write_char('{')?;
-            exp.write_error(w)?;
-            w.write_char('}')
+            self.write_error_placeholder(w, exp)
         } else {
             Ok(())
         }
@@ -92,9 +90,7 @@ impl<'scope, 'errors, R, M> Scope<'scope, 'errors, R, M> {
     {
         if self.travelled.contains(&pattern) {
             self.add_error(ResolverError::Cyclic);
-            w.write_char('{')?;
-            exp.write_error(w)?;
-            w.write_char('}')
+            self.write_error_placeholder(w, exp)
         } else {
             self.travelled.push(pattern);
             let result = pattern.write(w, self);
@@ -112,9 +108,30 @@ impl<'scope, 'errors, R, M> Scope<'scope, 'errors, R, M> {
         W: fmt::Write,
     {
         self.add_error(exp.into());
-        w.write_char('{')?;
-        exp.write_error(w)?;
-        w.write_char('}')
+        self.write_error_placeholder(w, exp)
+    }
+
+    // Renders an expression which failed to resolve, according to
+    // the `ErrorRendering` of the bundle.
+    pub(crate) fn write_error_placeholder<W, E>(&self, w: &mut W, exp: &E) -> fmt::Result
+    where
+        W: fmt::Write,
+        E: WriteValue,
+    {
+        match self.bundle.error_rendering {
+            ErrorRendering::Source => {
+                w.write_char('{')?;
+                exp.write_error(w)?;
+                w.write_char('}')
+            }
+            ErrorRendering::Empty => Ok(()),
+            ErrorRendering::Placeholder(placeholder) => w.write_str(placeholder),
+            ErrorRendering::Custom(func) => {
+                let mut source = String::new();
+                exp.write_error(&mut source)?;
+                func(w, &source)
+            }
+        }
     }
 
     pub fn get_arguments(
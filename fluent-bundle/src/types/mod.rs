@@ -29,7 +29,7 @@ use crate::resolver::Scope;
 use crate::resource::FluentResource;
 
 pub trait FluentType: fmt::Debug + AnyEq + 'static {
-    fn duplicate(&self) -> Box<dyn FluentType + Send>;
+    fn duplicate(&self) -> Box<dyn FluentType + Send + Sync>;
     fn as_string(&self, intls: &intl_memoizer::IntlLangMemoizer) -> Cow<'static, str>;
     fn as_string_threadsafe(
         &self,
@@ -37,7 +37,7 @@ pub trait FluentType: fmt::Debug + AnyEq + 'static {
     ) -> Cow<'static, str>;
 }
 
-impl PartialEq for dyn FluentType + Send {
+impl PartialEq for dyn FluentType + Send + Sync {
     fn eq(&self, other: &Self) -> bool {
         self.equals(other.as_any())
     }
@@ -69,7 +69,7 @@ impl<T: Any + PartialEq> AnyEq for T {
 pub enum FluentValue<'source> {
     String(Cow<'source, str>),
     Number(FluentNumber),
-    Custom(Box<dyn FluentType + Send>),
+    Custom(Box<dyn FluentType + Send + Sync>),
     None,
     Error,
 }
@@ -91,7 +91,7 @@ impl<'s> Clone for FluentValue<'s> {
             FluentValue::String(s) => FluentValue::String(s.clone()),
             FluentValue::Number(s) => FluentValue::Number(s.clone()),
             FluentValue::Custom(s) => {
-                let new_value: Box<dyn FluentType + Send> = s.duplicate();
+                let new_value: Box<dyn FluentType + Send + Sync> = s.duplicate();
                 FluentValue::Custom(new_value)
             }
             FluentValue::Error => FluentValue::Error,
@@ -0,0 +1,39 @@
+//! Adapters allowing the resolver, which writes to [`fmt::Write`],
+//! to stream its output into other kinds of sinks.
+
+use std::fmt;
+use std::io;
+
+/// Wraps an [`io::Write`] sink and exposes it as [`fmt::Write`].
+///
+/// [`fmt::Error`] carries no information, so the underlying [`io::Error`]
+/// is stored in the adapter and can be retrieved with [`IoWriter::into_result`].
+pub(crate) struct IoWriter<'w, W> {
+    inner: &'w mut W,
+    error: Option<io::Error>,
+}
+
+impl<'w, W: io::Write> IoWriter<'w, W> {
+    pub fn new(inner: &'w mut W) -> Self {
+        Self { inner, error: None }
+    }
+
+    /// Converts the result of a write into an [`io::Result`], preferring
+    /// the error reported by the underlying sink.
+    pub fn into_result(self, result: fmt::Result) -> io::Result<()> {
+        match (result, self.error) {
+            (Ok(()), _) => Ok(()),
+            (Err(_), Some(err)) => Err(err),
+            (Err(_), None) => Err(io::Error::other("formatter error")),
+        }
+    }
+}
+
+impl<'w, W: io::Write> fmt::Write for IoWriter<'w, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
@@ -1,4 +1,5 @@
-use fluent_bundle::{FluentBundle, FluentResource};
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource, FluentValue};
+use std::io;
 use unic_langid::LanguageIdentifier;
 
 #[test]
@@ -35,3 +36,215 @@ fn add_resource_override() {
 
     assert!(errors.is_empty());
 }
+
+#[test]
+fn write_pattern_io() {
+    let res = FluentResource::try_new("key = Hello, { $name }!".to_string()).unwrap();
+
+    let mut bundle = FluentBundle::default();
+    bundle.set_use_isolating(false);
+    bundle.add_resource(&res).expect("Failed to add a resource");
+
+    let value = bundle
+        .get_message("key")
+        .expect("Failed to retireve a message")
+        .value()
+        .expect("Failed to retireve a value of a message");
+
+    let mut args = FluentArgs::new();
+    args.set("name", "John");
+
+    let mut errors = vec![];
+    let mut output: Vec<u8> = vec![];
+    bundle
+        .write_pattern_io(&mut output, value, Some(&args), &mut errors)
+        .expect("Failed to write");
+    assert_eq!(output, b"Hello, John!");
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn write_pattern_io_error() {
+    struct FailingWriter;
+
+    impl io::Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let res = FluentResource::try_new("key = Value".to_string()).unwrap();
+
+    let mut bundle = FluentBundle::default();
+    bundle.add_resource(&res).expect("Failed to add a resource");
+
+    let value = bundle
+        .get_message("key")
+        .expect("Failed to retireve a message")
+        .value()
+        .expect("Failed to retireve a value of a message");
+
+    let mut errors = vec![];
+    let err = bundle
+        .write_pattern_io(&mut FailingWriter, value, None, &mut errors)
+        .expect_err("Writing should fail");
+    assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+}
+
+#[test]
+fn error_rendering() {
+    use fluent_bundle::bundle::ErrorRendering;
+    use std::fmt;
+
+    let res = FluentResource::try_new(
+        r#"
+var = Hello, { $name }!
+msg-ref = Hello, { missing }!
+attr-ref = Hello, { var.missing }!
+term-ref = Hello, { -missing }!
+"#
+        .to_string(),
+    )
+    .unwrap();
+
+    let mut bundle = FluentBundle::default();
+    bundle.set_use_isolating(false);
+    bundle.add_resource(&res).expect("Failed to add a resource");
+
+    let format = |bundle: &FluentBundle<&FluentResource>, id| {
+        let value = bundle
+            .get_message(id)
+            .expect("Failed to retireve a message")
+            .value()
+            .expect("Failed to retireve a value of a message");
+        let mut errors = vec![];
+        let result = bundle.format_pattern(value, None, &mut errors).to_string();
+        assert_eq!(errors.len(), 1);
+        result
+    };
+
+    assert_eq!(format(&bundle, "var"), "Hello, {$name}!");
+    assert_eq!(format(&bundle, "msg-ref"), "Hello, {missing}!");
+    assert_eq!(format(&bundle, "attr-ref"), "Hello, {var.missing}!");
+    assert_eq!(format(&bundle, "term-ref"), "Hello, {-missing}!");
+
+    bundle.set_error_rendering(ErrorRendering::Empty);
+    assert_eq!(format(&bundle, "var"), "Hello, !");
+    assert_eq!(format(&bundle, "msg-ref"), "Hello, !");
+
+    bundle.set_error_rendering(ErrorRendering::Placeholder("???"));
+    assert_eq!(format(&bundle, "var"), "Hello, ???!");
+    assert_eq!(format(&bundle, "term-ref"), "Hello, ???!");
+
+    fn loud(w: &mut dyn fmt::Write, source: &str) -> fmt::Result {
+        write!(w, "[MISSING: {}]", source)
+    }
+    bundle.set_error_rendering(ErrorRendering::Custom(loud));
+    assert_eq!(format(&bundle, "var"), "Hello, [MISSING: $name]!");
+    assert_eq!(
+        format(&bundle, "attr-ref"),
+        "Hello, [MISSING: var.missing]!"
+    );
+}
+
+#[test]
+fn error_rendering_of_functions() {
+    use fluent_bundle::bundle::ErrorRendering;
+
+    let res = FluentResource::try_new("fn-ref = Hello, { FAIL() }!".to_string()).unwrap();
+
+    let mut bundle = FluentBundle::default();
+    bundle.set_use_isolating(false);
+    bundle
+        .add_function("FAIL", |_, _| FluentValue::Error)
+        .expect("Failed to add a function");
+    bundle.add_resource(&res).expect("Failed to add a resource");
+
+    let format = |bundle: &FluentBundle<&FluentResource>| {
+        let value = bundle
+            .get_message("fn-ref")
+            .expect("Failed to retireve a message")
+            .value()
+            .expect("Failed to retireve a value of a message");
+        let mut errors = vec![];
+        bundle.format_pattern(value, None, &mut errors).to_string()
+    };
+
+    assert_eq!(format(&bundle), "Hello, {FAIL()}!");
+
+    bundle.set_error_rendering(ErrorRendering::Empty);
+    assert_eq!(format(&bundle), "Hello, !");
+
+    bundle.set_error_rendering(ErrorRendering::Placeholder("???"));
+    assert_eq!(format(&bundle), "Hello, ???!");
+}
+
+#[test]
+fn isolation_strategy() {
+    use fluent_bundle::bundle::IsolationStrategy;
+
+    let res = FluentResource::try_new("key = Hello, { $name }!".to_string()).unwrap();
+
+    let format = |locale: &str, strategy, name: &str| {
+        let mut bundle = FluentBundle::new(vec![locale.parse().unwrap()]);
+        bundle.set_isolation_strategy(strategy);
+        bundle.add_resource(&res).expect("Failed to add a resource");
+
+        let value = bundle
+            .get_message("key")
+            .expect("Failed to retireve a message")
+            .value()
+            .expect("Failed to retireve a value of a message");
+
+        let mut args = FluentArgs::new();
+        args.set("name", name);
+
+        let mut errors = vec![];
+        let result = bundle
+            .format_pattern(value, Some(&args), &mut errors)
+            .to_string();
+        assert!(errors.is_empty());
+        result
+    };
+
+    assert_eq!(
+        format("en-US", IsolationStrategy::None, "John"),
+        "Hello, John!"
+    );
+    assert_eq!(
+        format("en-US", IsolationStrategy::Isolate, "John"),
+        "Hello, \u{2068}John\u{2069}!"
+    );
+    assert_eq!(
+        format("en-US", IsolationStrategy::Marks, "John"),
+        "Hello, \u{200E}John\u{200E}!"
+    );
+    assert_eq!(
+        format("ar", IsolationStrategy::Marks, "John"),
+        "Hello, \u{200F}John\u{200F}!"
+    );
+    assert_eq!(
+        format("en-US", IsolationStrategy::DirectionAware, "John"),
+        "Hello, John!"
+    );
+    assert_eq!(
+        format("en-US", IsolationStrategy::DirectionAware, "שלום"),
+        "Hello, \u{2068}שלום\u{2069}!"
+    );
+    assert_eq!(
+        format("ar", IsolationStrategy::DirectionAware, "John"),
+        "Hello, \u{2068}John\u{2069}!"
+    );
+    assert_eq!(
+        format("ar", IsolationStrategy::DirectionAware, "سلام"),
+        "Hello, سلام!"
+    );
+    assert_eq!(
+        format("ar", IsolationStrategy::DirectionAware, "123"),
+        "Hello, 123!"
+    );
+}
@@ -0,0 +1,102 @@
+use std::sync::Arc;
+use std::thread;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use unic_langid::langid;
+
+fn get_bundle(resource: &Arc<FluentResource>) -> FluentBundle<Arc<FluentResource>> {
+    let mut bundle = FluentBundle::new_concurrent(vec![langid!("pl")]);
+    bundle.set_use_isolating(false);
+    bundle
+        .add_resource(Arc::clone(resource))
+        .expect("Failed to add a resource");
+    bundle
+        .add_function("DOUBLE", |positional, _named| match positional {
+            [FluentValue::Number(n)] => FluentValue::from(n.value * 2.0),
+            _ => FluentValue::Error,
+        })
+        .expect("Failed to add a function");
+    bundle
+}
+
+#[test]
+fn concurrent_bundle_shared_across_threads() {
+    let resource = Arc::new(
+        FluentResource::try_new(
+            r#"
+emails = { $count ->
+    [one] { $count } wiadomość
+    [few] { $count } wiadomości
+   *[many] { $count } wiadomości
+}
+double = { DOUBLE($count) }
+"#
+            .to_string(),
+        )
+        .expect("Failed to parse a resource"),
+    );
+
+    let bundle = Arc::new(get_bundle(&resource));
+    let bundle2 = Arc::new(get_bundle(&resource));
+
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let bundle = if i % 2 == 0 {
+                Arc::clone(&bundle)
+            } else {
+                Arc::clone(&bundle2)
+            };
+            thread::spawn(move || {
+                let mut results = vec![];
+                for count in 1..=5 {
+                    let mut args = FluentArgs::new();
+                    args.set("count", count);
+
+                    let mut errors = vec![];
+                    let msg = bundle.get_message("emails").expect("Message exists");
+                    let value = msg.value().expect("Message has a value");
+                    results.push(
+                        bundle
+                            .format_pattern(value, Some(&args), &mut errors)
+                            .to_string(),
+                    );
+
+                    let msg = bundle.get_message("double").expect("Message exists");
+                    let value = msg.value().expect("Message has a value");
+                    results.push(
+                        bundle
+                            .format_pattern(value, Some(&args), &mut errors)
+                            .to_string(),
+                    );
+                    assert!(errors.is_empty());
+                }
+                results
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        let results = handle.join().expect("Thread panicked");
+        assert_eq!(
+            results,
+            vec![
+                "1 wiadomość",
+                "2",
+                "2 wiadomości",
+                "4",
+                "3 wiadomości",
+                "6",
+                "4 wiadomości",
+                "8",
+                "5 wiadomości",
+                "10",
+            ]
+        );
+    }
+
+    // Both bundles and the test itself hold a reference to the resource.
+    drop(bundle);
+    drop(bundle2);
+    assert_eq!(Arc::strong_count(&resource), 1);
+}
@@ -20,9 +20,9 @@
 
 use std::{env, fs, io, path::PathBuf, str::FromStr};
 
-use fluent_bundle::{FluentArgs, FluentBundle, FluentResource};
+use fluent_bundle::{FluentArgs, FluentBundle, FluentError, FluentResource};
 use fluent_fallback::{
-    generator::{BundleGenerator, FluentBundleResult},
+    generator::{BundleGenerator, FluentBundleResult, IterStream},
     types::ResourceId,
     Localization,
 };
@@ -205,22 +205,12 @@ impl Iterator for BundleIter {
     }
 }
 
-impl futures::Stream for BundleIter {
-    type Item = FluentBundleResult<FluentResource>;
-
-    fn poll_next(
-        self: std::pin::Pin<&mut Self>,
-        _cx: &mut std::task::Context<'_>,
-    ) -> std::task::Poll<Option<Self::Item>> {
-        todo!()
-    }
-}
-
 impl BundleGenerator for Bundles {
     type Resource = FluentResource;
+    type Error = FluentError;
     type LocalesIter = std::vec::IntoIter<LanguageIdentifier>;
     type Iter = BundleIter;
-    type Stream = BundleIter;
+    type Stream = IterStream<BundleIter>;
 
     fn bundles_iter(
         &self,
@@ -233,4 +223,12 @@ impl BundleGenerator for Bundles {
             res_ids,
         }
     }
+
+    fn bundles_stream(
+        &self,
+        locales: std::vec::IntoIter<LanguageIdentifier>,
+        res_ids: Vec<ResourceId>,
+    ) -> Self::Stream {
+        self.bundles_iter(locales, res_ids).into()
+    }
 }
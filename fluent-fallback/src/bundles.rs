@@ -1,69 +1,296 @@
+// `LocalizationError` is returned as is by the synchronous public methods.
+#![allow(clippy::result_large_err)]
+
 use crate::{
-    cache::{AsyncCache, Cache},
+    cache::{AsyncCache, Cache, SegmentsIter, SegmentsStream},
     env::LocalesProvider,
-    errors::LocalizationError,
-    generator::{BundleGenerator, BundleIterator, BundleStream},
-    types::{L10nAttribute, L10nKey, L10nMessage, ResourceId},
+    errors::{ErrorReporter, LocalizationError},
+    generator::{
+        BundleGenerator, BundleIterator, BundleStream, FluentBundleResult, GeneratorError,
+    },
+    last_resort::LastResort,
+    memoizer::LocalizationMemoizer,
+    segments::{plan, same_bundle, Batch, BundleCache, Plan, Segment},
+    types::{FallbackReason, FallbackReport, L10nAttribute, L10nKey, L10nMessage, ResourceId},
+};
+use fluent_bundle::{
+    bundle, memoizer::MemoizerKind, FluentArgs, FluentAttribute, FluentError, FluentMessage,
+    FluentResource,
 };
-use fluent_bundle::{FluentArgs, FluentBundle, FluentError};
-use std::borrow::Cow;
+use intl_memoizer::IntlLangMemoizer;
+use std::borrow::{Borrow, Cow};
+use std::ops::Deref;
+use unic_langid::LanguageIdentifier;
+
+/// A segment of a batch shared by the bundles generated from it.
+type CachedSegment<M, C> = Segment<<M as LocalizationMemoizer>::Shared<Batch<C>>>;
 
-pub enum BundlesInner<G>
+enum BundlesInner<G, M>
 where
-    G: BundleGenerator,
+    G: BundleGenerator<M>,
+    M: LocalizationMemoizer,
 {
-    Iter(Cache<G::Iter, G::Resource>),
-    Stream(AsyncCache<G::Stream, G::Resource>),
+    Iter(Vec<CachedSegment<M, Cache<G::Iter>>>),
+    Stream(Vec<CachedSegment<M, AsyncCache<G::Stream>>>),
 }
 
-pub struct Bundles<G>(BundlesInner<G>)
+/// The bundles of a [`Localization`](crate::Localization), generated lazily
+/// and cached, which translations are formatted with.
+///
+/// The bundles use the memoizer `M`, see [`LocalizationMemoizer`].
+pub struct Bundles<G, M = IntlLangMemoizer>
 where
-    G: BundleGenerator;
+    G: BundleGenerator<M>,
+    M: LocalizationMemoizer,
+{
+    inner: BundlesInner<G, M>,
+    last_resort: M::Shared<LastResort<bundle::FluentBundle<FluentResource, M>>>,
+    error_reporter: M::Shared<ErrorReporter<M::Sink>>,
+    attribute_fallback: bool,
+}
 
-impl<G> Bundles<G>
+impl<G, M> Bundles<G, M>
 where
-    G: BundleGenerator,
+    G: BundleGenerator<M>,
+    M: LocalizationMemoizer,
     G::Iter: BundleIterator,
 {
-    pub fn prefetch_sync(&self) {
-        match &self.0 {
-            BundlesInner::Iter(iter) => iter.prefetch(),
-            BundlesInner::Stream(_) => panic!("Can't prefetch a sync bundle set asynchronously"),
+    pub fn prefetch_sync(&self) -> Result<(), LocalizationError> {
+        match &self.inner {
+            BundlesInner::Iter(segments) => {
+                for batch in batches(segments) {
+                    batch.cache.prefetch();
+                }
+                Ok(())
+            }
+            BundlesInner::Stream(_) => Err(LocalizationError::SyncRequestInAsyncMode),
         }
     }
 }
 
-impl<G> Bundles<G>
+impl<G, M> Bundles<G, M>
 where
-    G: BundleGenerator,
+    G: BundleGenerator<M>,
+    M: LocalizationMemoizer,
+    G::Iter: BundleIterator,
     G::Stream: BundleStream,
 {
     pub async fn prefetch_async(&self) {
-        match &self.0 {
-            BundlesInner::Iter(_) => panic!("Can't prefetch a async bundle set synchronously"),
-            BundlesInner::Stream(stream) => stream.prefetch().await,
+        match &self.inner {
+            BundlesInner::Iter(segments) => {
+                for batch in batches(segments) {
+                    batch.cache.prefetch();
+                }
+            }
+            BundlesInner::Stream(segments) => {
+                for batch in batches(segments) {
+                    batch.cache.prefetch().await;
+                }
+            }
         }
     }
 }
 
-impl<G> Bundles<G>
+/// Returns the distinct batches of consecutive `segments`.
+fn batches<'a, B, C: 'a>(segments: &'a [Segment<B>]) -> impl Iterator<Item = &'a Batch<C>>
+where
+    B: Deref<Target = Batch<C>>,
+{
+    segments
+        .iter()
+        .enumerate()
+        .filter(move |(idx, segment)| {
+            *idx == 0 || !std::ptr::eq(&*segments[idx - 1].batch, &*segment.batch)
+        })
+        .map(|(_, segment)| &*segment.batch)
+}
+
+/// Returns the segment of a batch serving all of its locales.
+fn whole<M, C>(
+    locales: Vec<LanguageIdentifier>,
+    res_ids: Vec<ResourceId>,
+    cache: C,
+) -> CachedSegment<M, C>
+where
+    M: LocalizationMemoizer,
+{
+    Segment {
+        range: 0..locales.len(),
+        batch: M::share(Batch::new(locales, res_ids, cache)),
+    }
+}
+
+/// Returns the segments of the bundles of `locales` generated from `res_ids`,
+/// taken from the `previous` segments when they are the same, see
+/// [`same_bundle`].
+///
+/// The other locales are generated by `generate` in runs if the provider
+/// supports [`LocalesProvider::locales_subset`], or else by one batch of all
+/// the locales.
+fn reuse_segments<M, C, P, H, F>(
+    previous: &[CachedSegment<M, C>],
+    locales: &[LanguageIdentifier],
+    res_ids: &[ResourceId],
+    provider: &P,
+    has_resource: H,
+    generate: F,
+) -> Vec<CachedSegment<M, C>>
+where
+    M: LocalizationMemoizer,
+    C: BundleCache,
+    P: LocalesProvider,
+    H: Fn(&LanguageIdentifier, &ResourceId) -> bool,
+    F: Fn(P::Iter) -> C,
+{
+    let mut full: Option<CachedSegment<M, C>> = None;
+    plan(previous, locales, |batch, locale| {
+        same_bundle(&batch.res_ids, res_ids, |res_id| {
+            has_resource(locale, res_id)
+        })
+    })
+    .into_iter()
+    .map(|plan| match plan {
+        Plan::Reuse(segment) => segment,
+        Plan::Generate(range) => {
+            let run = locales[range.clone()].to_vec();
+            match provider.locales_subset(run.clone()) {
+                Some(iter) => whole::<M, _>(run, res_ids.to_vec(), generate(iter)),
+                None => {
+                    let full = full.get_or_insert_with(|| {
+                        whole::<M, _>(
+                            locales.to_vec(),
+                            res_ids.to_vec(),
+                            generate(provider.locales()),
+                        )
+                    });
+                    Segment {
+                        batch: full.batch.clone(),
+                        range,
+                    }
+                }
+            }
+        }
+    })
+    .collect()
+}
+
+impl<G, M> Bundles<G, M>
 where
-    G: BundleGenerator,
+    G: BundleGenerator<M>,
+    M: LocalizationMemoizer,
 {
     pub fn new<P>(sync: bool, res_ids: Vec<ResourceId>, generator: &G, provider: &P) -> Self
     where
-        G: BundleGenerator<LocalesIter = P::Iter>,
+        G: BundleGenerator<M, LocalesIter = P::Iter>,
         P: LocalesProvider,
     {
-        Self(if sync {
-            BundlesInner::Iter(Cache::new(
-                generator.bundles_iter(provider.locales(), res_ids),
-            ))
+        let locales = provider.locales().collect();
+        let inner = if sync {
+            BundlesInner::Iter(vec![whole::<M, _>(
+                locales,
+                res_ids.clone(),
+                Cache::new(generator.bundles_iter(provider.locales(), res_ids)),
+            )])
         } else {
-            BundlesInner::Stream(AsyncCache::new(
-                generator.bundles_stream(provider.locales(), res_ids),
-            ))
-        })
+            BundlesInner::Stream(vec![whole::<M, _>(
+                locales,
+                res_ids.clone(),
+                AsyncCache::new(generator.bundles_stream(provider.locales(), res_ids)),
+            )])
+        };
+        Self::from_inner(inner)
+    }
+
+    /// Creates the bundles like [`Bundles::new`], taking the bundles which
+    /// are still the same from `previous`: those of the locales which are
+    /// still present, unless the resources they are generated from changed.
+    ///
+    /// Only the other bundles are generated again, see [`reuse_segments`].
+    pub(crate) fn reusing<P>(
+        previous: &Self,
+        sync: bool,
+        res_ids: Vec<ResourceId>,
+        generator: &G,
+        provider: &P,
+    ) -> Self
+    where
+        G: BundleGenerator<M, LocalesIter = P::Iter>,
+        P: LocalesProvider,
+    {
+        let locales: Vec<_> = provider.locales().collect();
+        let has_resource = |locale: &_, res_id: &_| generator.has_resource(locale, res_id);
+        let inner = match (sync, &previous.inner) {
+            (true, BundlesInner::Iter(segments)) => {
+                BundlesInner::Iter(reuse_segments::<M, _, _, _, _>(
+                    segments,
+                    &locales,
+                    &res_ids,
+                    provider,
+                    has_resource,
+                    |iter| Cache::new(generator.bundles_iter(iter, res_ids.clone())),
+                ))
+            }
+            (false, BundlesInner::Stream(segments)) => {
+                BundlesInner::Stream(reuse_segments::<M, _, _, _, _>(
+                    segments,
+                    &locales,
+                    &res_ids,
+                    provider,
+                    has_resource,
+                    |iter| AsyncCache::new(generator.bundles_stream(iter, res_ids.clone())),
+                ))
+            }
+            _ => return Self::new(sync, res_ids, generator, provider),
+        };
+        Self::from_inner(inner)
+    }
+
+    fn from_inner(inner: BundlesInner<G, M>) -> Self {
+        Self {
+            inner,
+            last_resort: M::share(Default::default()),
+            error_reporter: M::share(Default::default()),
+            attribute_fallback: false,
+        }
+    }
+
+    pub(crate) fn with_last_resort(
+        mut self,
+        last_resort: M::Shared<LastResort<bundle::FluentBundle<FluentResource, M>>>,
+    ) -> Self {
+        self.last_resort = last_resort;
+        self
+    }
+
+    pub(crate) fn with_error_reporter(
+        mut self,
+        error_reporter: M::Shared<ErrorReporter<M::Sink>>,
+    ) -> Self {
+        self.error_reporter = error_reporter;
+        self
+    }
+
+    pub(crate) fn with_attribute_fallback(mut self, attribute_fallback: bool) -> Self {
+        self.attribute_fallback = attribute_fallback;
+        self
+    }
+
+    /// Returns the errors of the first bundle, the one of the primary locale.
+    pub(crate) async fn primary_errors(&self) -> Vec<LocalizationError> {
+        match &self.inner {
+            BundlesInner::Iter(segments) => bundle_errors(SegmentsIter::new(segments).next()),
+            BundlesInner::Stream(segments) => {
+                bundle_errors(SegmentsStream::new(segments).next().await)
+            }
+        }
+    }
+
+    /// The synchronous variant of [`Bundles::primary_errors`].
+    pub(crate) fn primary_errors_sync(&self) -> Result<Vec<LocalizationError>, LocalizationError> {
+        match &self.inner {
+            BundlesInner::Iter(segments) => Ok(bundle_errors(SegmentsIter::new(segments).next())),
+            BundlesInner::Stream(_) => Err(LocalizationError::SyncRequestInAsyncMode),
+        }
     }
 
     pub async fn format_value<'l>(
@@ -72,12 +299,18 @@ where
         args: Option<&'l FluentArgs<'_>>,
         errors: &mut Vec<LocalizationError>,
     ) -> Option<Cow<'l, str>> {
-        match &self.0 {
-            BundlesInner::Iter(cache) => Self::format_value_from_iter(cache, id, args, errors),
-            BundlesInner::Stream(stream) => {
-                Self::format_value_from_stream(stream, id, args, errors).await
+        let start = errors.len();
+        let value = match &self.inner {
+            BundlesInner::Iter(segments) => {
+                Self::format_value_from_iter(segments, id, args, errors)
             }
-        }
+            BundlesInner::Stream(segments) => {
+                Self::format_value_from_stream(segments, id, args, errors).await
+            }
+        };
+        let value = value.or_else(|| self.last_resort.format_value(id, args, errors));
+        self.error_reporter.report(&errors[start..]);
+        value
     }
 
     pub async fn format_values<'l>(
@@ -85,12 +318,11 @@ where
         keys: &'l [L10nKey<'l>],
         errors: &mut Vec<LocalizationError>,
     ) -> Vec<Option<Cow<'l, str>>> {
-        match &self.0 {
-            BundlesInner::Iter(cache) => Self::format_values_from_iter(cache, keys, errors),
-            BundlesInner::Stream(stream) => {
-                Self::format_values_from_stream(stream, keys, errors).await
-            }
-        }
+        self.format_values_with_report(keys, errors)
+            .await
+            .into_iter()
+            .map(|report| report.value)
+            .collect()
     }
 
     pub async fn format_messages<'l>(
@@ -98,12 +330,71 @@ where
         keys: &'l [L10nKey<'l>],
         errors: &mut Vec<LocalizationError>,
     ) -> Vec<Option<L10nMessage<'l>>> {
-        match &self.0 {
-            BundlesInner::Iter(cache) => Self::format_messages_from_iter(cache, keys, errors),
-            BundlesInner::Stream(stream) => {
-                Self::format_messages_from_stream(stream, keys, errors).await
+        self.format_messages_with_report(keys, errors)
+            .await
+            .into_iter()
+            .map(|report| report.value)
+            .collect()
+    }
+
+    /// Formats the values of `keys` like [`Bundles::format_values`], and
+    /// reports the locale which provided each of them, as well as the
+    /// locales which were tried before it.
+    pub async fn format_values_with_report<'l>(
+        &'l self,
+        keys: &'l [L10nKey<'l>],
+        errors: &mut Vec<LocalizationError>,
+    ) -> Vec<FallbackReport<Cow<'l, str>>> {
+        let start = errors.len();
+        let mut reports = match &self.inner {
+            BundlesInner::Iter(segments) => {
+                Self::format_values_with_report_from_iter(segments, keys, errors)
+            }
+            BundlesInner::Stream(segments) => {
+                Self::format_values_with_report_from_stream(segments, keys, errors).await
+            }
+        };
+        self.last_resort.format_missing_values(
+            keys,
+            reports.iter_mut().map(|r| &mut r.value),
+            errors,
+        );
+        self.error_reporter.report(&errors[start..]);
+        reports
+    }
+
+    /// Formats `keys` like [`Bundles::format_messages`], and reports the
+    /// locale which provided each of them, as well as the locales which
+    /// were tried before it.
+    pub async fn format_messages_with_report<'l>(
+        &'l self,
+        keys: &'l [L10nKey<'l>],
+        errors: &mut Vec<LocalizationError>,
+    ) -> Vec<FallbackReport<L10nMessage<'l>>> {
+        let start = errors.len();
+        let mut reports = match &self.inner {
+            BundlesInner::Iter(segments) => Self::format_messages_from_iter(segments, keys, errors),
+            BundlesInner::Stream(segments) => {
+                Self::format_messages_from_stream(segments, keys, errors).await
+            }
+        };
+        if self.attribute_fallback {
+            match &self.inner {
+                BundlesInner::Iter(segments) => {
+                    Self::fill_attributes_from_iter(segments, keys, &mut reports, errors)
+                }
+                BundlesInner::Stream(segments) => {
+                    Self::fill_attributes_from_stream(segments, keys, &mut reports, errors).await
+                }
             }
         }
+        self.last_resort.format_missing_messages(
+            keys,
+            reports.iter_mut().map(|r| &mut r.value),
+            errors,
+        );
+        self.error_reporter.report(&errors[start..]);
+        reports
     }
 
     pub fn format_value_sync<'l>(
@@ -112,10 +403,16 @@ where
         args: Option<&'l FluentArgs>,
         errors: &mut Vec<LocalizationError>,
     ) -> Result<Option<Cow<'l, str>>, LocalizationError> {
-        match &self.0 {
-            BundlesInner::Iter(cache) => Ok(Self::format_value_from_iter(cache, id, args, errors)),
-            BundlesInner::Stream(_) => Err(LocalizationError::SyncRequestInAsyncMode),
-        }
+        let start = errors.len();
+        let value = match &self.inner {
+            BundlesInner::Iter(segments) => {
+                Self::format_value_from_iter(segments, id, args, errors)
+            }
+            BundlesInner::Stream(_) => return Err(LocalizationError::SyncRequestInAsyncMode),
+        };
+        let value = value.or_else(|| self.last_resort.format_value(id, args, errors));
+        self.error_reporter.report(&errors[start..]);
+        Ok(value)
     }
 
     pub fn format_values_sync<'l>(
@@ -123,10 +420,8 @@ where
         keys: &'l [L10nKey<'l>],
         errors: &mut Vec<LocalizationError>,
     ) -> Result<Vec<Option<Cow<'l, str>>>, LocalizationError> {
-        match &self.0 {
-            BundlesInner::Iter(cache) => Ok(Self::format_values_from_iter(cache, keys, errors)),
-            BundlesInner::Stream(_) => Err(LocalizationError::SyncRequestInAsyncMode),
-        }
+        let reports = self.format_values_with_report_sync(keys, errors)?;
+        Ok(reports.into_iter().map(|report| report.value).collect())
     }
 
     pub fn format_messages_sync<'l>(
@@ -134,10 +429,56 @@ where
         keys: &'l [L10nKey<'l>],
         errors: &mut Vec<LocalizationError>,
     ) -> Result<Vec<Option<L10nMessage<'l>>>, LocalizationError> {
-        match &self.0 {
-            BundlesInner::Iter(cache) => Ok(Self::format_messages_from_iter(cache, keys, errors)),
-            BundlesInner::Stream(_) => Err(LocalizationError::SyncRequestInAsyncMode),
-        }
+        let reports = self.format_messages_with_report_sync(keys, errors)?;
+        Ok(reports.into_iter().map(|report| report.value).collect())
+    }
+
+    /// The synchronous variant of [`Bundles::format_values_with_report`].
+    pub fn format_values_with_report_sync<'l>(
+        &'l self,
+        keys: &'l [L10nKey<'l>],
+        errors: &mut Vec<LocalizationError>,
+    ) -> Result<Vec<FallbackReport<Cow<'l, str>>>, LocalizationError> {
+        let start = errors.len();
+        let mut reports = match &self.inner {
+            BundlesInner::Iter(segments) => {
+                Self::format_values_with_report_from_iter(segments, keys, errors)
+            }
+            BundlesInner::Stream(_) => return Err(LocalizationError::SyncRequestInAsyncMode),
+        };
+        self.last_resort.format_missing_values(
+            keys,
+            reports.iter_mut().map(|r| &mut r.value),
+            errors,
+        );
+        self.error_reporter.report(&errors[start..]);
+        Ok(reports)
+    }
+
+    /// The synchronous variant of [`Bundles::format_messages_with_report`].
+    pub fn format_messages_with_report_sync<'l>(
+        &'l self,
+        keys: &'l [L10nKey<'l>],
+        errors: &mut Vec<LocalizationError>,
+    ) -> Result<Vec<FallbackReport<L10nMessage<'l>>>, LocalizationError> {
+        let start = errors.len();
+        let mut reports = match &self.inner {
+            BundlesInner::Iter(segments) => {
+                let mut reports = Self::format_messages_from_iter(segments, keys, errors);
+                if self.attribute_fallback {
+                    Self::fill_attributes_from_iter(segments, keys, &mut reports, errors);
+                }
+                reports
+            }
+            BundlesInner::Stream(_) => return Err(LocalizationError::SyncRequestInAsyncMode),
+        };
+        self.last_resort.format_missing_messages(
+            keys,
+            reports.iter_mut().map(|r| &mut r.value),
+            errors,
+        );
+        self.error_reporter.report(&errors[start..]);
+        Ok(reports)
     }
 }
 
@@ -147,7 +488,7 @@ macro_rules! format_value_from_inner {
 
         while let Some(bundle) = $step {
             let bundle = bundle.as_ref().unwrap_or_else(|(bundle, err)| {
-                $errors.extend(err.iter().cloned().map(Into::into));
+                $errors.extend($crate::bundles::localization_errors(bundle, err));
                 bundle
             });
 
@@ -157,7 +498,7 @@ macro_rules! format_value_from_inner {
                     let mut format_errors = vec![];
                     let result = bundle.format_pattern(value, $args, &mut format_errors);
                     if !format_errors.is_empty() {
-                        $errors.push(LocalizationError::Resolver {
+                        $errors.push($crate::LocalizationError::Resolver {
                             id: $id.to_string(),
                             locale: bundle.locales[0].clone(),
                             errors: format_errors,
@@ -165,25 +506,25 @@ macro_rules! format_value_from_inner {
                     }
                     return Some(result);
                 } else {
-                    $errors.push(LocalizationError::MissingValue {
+                    $errors.push($crate::LocalizationError::MissingValue {
                         id: $id.to_string(),
                         locale: Some(bundle.locales[0].clone()),
                     });
                 }
             } else {
-                $errors.push(LocalizationError::MissingMessage {
+                $errors.push($crate::LocalizationError::MissingMessage {
                     id: $id.to_string(),
                     locale: Some(bundle.locales[0].clone()),
                 });
             }
         }
         if found_message {
-            $errors.push(LocalizationError::MissingValue {
+            $errors.push($crate::LocalizationError::MissingValue {
                 id: $id.to_string(),
                 locale: None,
             });
         } else {
-            $errors.push(LocalizationError::MissingMessage {
+            $errors.push($crate::LocalizationError::MissingMessage {
                 id: $id.to_string(),
                 locale: None,
             });
@@ -192,58 +533,90 @@ macro_rules! format_value_from_inner {
     };
 }
 
-#[derive(Clone)]
-enum Value<'l> {
-    Value(Cow<'l, str>),
-    MissingValue,
-    None,
-}
-
-macro_rules! format_values_from_inner {
+macro_rules! format_messages_from_inner {
     ($step:expr, $keys:expr, $errors:expr) => {
-        let mut cells = vec![Value::None; $keys.len()];
+        let mut reports: Vec<_> = $keys
+            .iter()
+            .map(|_| $crate::types::FallbackReport::default())
+            .collect();
+        // The requested attributes which no locale provided yet, for each key.
+        let mut missing = vec![vec![]; $keys.len()];
 
         while let Some(bundle) = $step {
             let bundle = bundle.as_ref().unwrap_or_else(|(bundle, err)| {
-                $errors.extend(err.iter().cloned().map(Into::into));
+                $errors.extend($crate::bundles::localization_errors(bundle, err));
                 bundle
             });
+            let locale = &bundle.locales[0];
 
             let mut has_missing = false;
-
-            for (key, cell) in $keys
+            for ((key, report), missing) in $keys
                 .iter()
-                .zip(&mut cells)
-                .filter(|(_, cell)| !matches!(cell, Value::Value(_)))
+                .zip(&mut reports)
+                .zip(&mut missing)
+                .filter(|((_, report), missing)| report.value.is_none() || !missing.is_empty())
             {
-                if let Some(msg) = bundle.get_message(&key.id) {
-                    if let Some(value) = msg.value() {
-                        let mut format_errors = vec![];
-                        *cell = Value::Value(bundle.format_pattern(
-                            value,
-                            key.args.as_ref(),
+                let msg = match bundle.get_message(&key.id) {
+                    Some(msg) => msg,
+                    None => {
+                        has_missing = true;
+                        $errors.push($crate::LocalizationError::MissingMessage {
+                            id: key.id.to_string(),
+                            locale: Some(locale.clone()),
+                        });
+                        report.attempts.push($crate::types::FallbackAttempt {
+                            locale: locale.clone(),
+                            reason: $crate::types::FallbackReason::MissingMessage,
+                        });
+                        continue;
+                    }
+                };
+
+                let mut format_errors = vec![];
+                match &mut report.value {
+                    None => {
+                        *missing = $crate::bundles::missing_attributes(&msg, key);
+                        report.value = Some($crate::bundles::format_message(
+                            bundle,
+                            &msg,
+                            key,
                             &mut format_errors,
                         ));
-                        if !format_errors.is_empty() {
-                            $errors.push(LocalizationError::Resolver {
-                                id: key.id.to_string(),
-                                locale: bundle.locales[0].clone(),
-                                errors: format_errors,
-                            });
+                        report.locale = Some(locale.clone());
+                    }
+                    Some(message) => missing.retain(|name| match msg.get_attribute(name) {
+                        Some(attr) => {
+                            message.attributes.push($crate::bundles::format_attribute(
+                                bundle,
+                                attr,
+                                key,
+                                &mut format_errors,
+                            ));
+                            false
                         }
-                    } else {
-                        *cell = Value::MissingValue;
-                        has_missing = true;
-                        $errors.push(LocalizationError::MissingValue {
+                        None => true,
+                    }),
+                }
+                if !format_errors.is_empty() {
+                    $errors.push($crate::LocalizationError::Resolver {
+                        id: key.id.to_string(),
+                        locale: locale.clone(),
+                        errors: format_errors,
+                    });
+                }
+
+                if !missing.is_empty() {
+                    has_missing = true;
+                    for name in missing.iter() {
+                        $errors.push($crate::LocalizationError::MissingAttribute {
                             id: key.id.to_string(),
-                            locale: Some(bundle.locales[0].clone()),
+                            attribute: name.to_string(),
+                            locale: locale.clone(),
                         });
                     }
-                } else {
-                    has_missing = true;
-                    $errors.push(LocalizationError::MissingMessage {
-                        id: key.id.to_string(),
-                        locale: Some(bundle.locales[0].clone()),
+                    report.attempts.push($crate::types::FallbackAttempt {
+                        locale: locale.clone(),
+                        reason: $crate::types::FallbackReason::MissingAttribute,
                     });
                 }
             }
@@ -252,174 +625,401 @@ macro_rules! format_values_from_inner {
             }
         }
 
-        return $keys
-            .iter()
-            .zip(cells)
-            .map(|(key, value)| match value {
-                Value::Value(value) => Some(value),
-                Value::MissingValue => {
-                    $errors.push(LocalizationError::MissingValue {
-                        id: key.id.to_string(),
-                        locale: None,
-                    });
-                    None
+        for (key, report) in $keys.iter().zip(&mut reports) {
+            match (&mut report.value, &key.attributes) {
+                (Some(message), Some(names)) => {
+                    // Attributes taken from later locales go in the requested order.
+                    message
+                        .attributes
+                        .sort_by_key(|attr| names.iter().position(|name| *name == attr.name));
                 }
-                Value::None => {
-                    $errors.push(LocalizationError::MissingMessage {
+                (Some(_), None) => {}
+                (None, _) => {
+                    $errors.push($crate::LocalizationError::MissingMessage {
                         id: key.id.to_string(),
                         locale: None,
                     });
-                    None
                 }
-            })
-            .collect();
+            }
+        }
+
+        return reports;
     };
 }
 
-macro_rules! format_messages_from_inner {
+macro_rules! format_values_with_report_from_inner {
     ($step:expr, $keys:expr, $errors:expr) => {
-        let mut result = vec![None; $keys.len()];
-
-        let mut is_complete = false;
+        let mut reports: Vec<_> = $keys
+            .iter()
+            .map(|_| $crate::types::FallbackReport::default())
+            .collect();
 
         while let Some(bundle) = $step {
             let bundle = bundle.as_ref().unwrap_or_else(|(bundle, err)| {
-                $errors.extend(err.iter().cloned().map(Into::into));
+                $errors.extend($crate::bundles::localization_errors(bundle, err));
                 bundle
             });
+            let locale = &bundle.locales[0];
 
             let mut has_missing = false;
-            for (key, cell) in $keys
+            for (key, report) in $keys
                 .iter()
-                .zip(&mut result)
-                .filter(|(_, cell)| cell.is_none())
+                .zip(&mut reports)
+                .filter(|(_, report)| report.value.is_none())
             {
                 let mut format_errors = vec![];
-                let msg = Self::format_message_from_bundle(bundle, key, &mut format_errors);
-
-                if msg.is_none() {
-                    has_missing = true;
-                    $errors.push(LocalizationError::MissingMessage {
-                        id: key.id.to_string(),
-                        locale: Some(bundle.locales[0].clone()),
-                    });
-                } else if !format_errors.is_empty() {
-                    $errors.push(LocalizationError::Resolver {
-                        id: key.id.to_string(),
-                        locale: bundle.locales.get(0).cloned().unwrap(),
-                        errors: format_errors,
-                    });
+                match $crate::bundles::format_value_for_report(bundle, key, &mut format_errors) {
+                    Ok(value) => {
+                        if !format_errors.is_empty() {
+                            $errors.push($crate::LocalizationError::Resolver {
+                                id: key.id.to_string(),
+                                locale: locale.clone(),
+                                errors: format_errors,
+                            });
+                        }
+                        report.value = Some(value);
+                        report.locale = Some(locale.clone());
+                    }
+                    Err(reason) => {
+                        has_missing = true;
+                        $errors.push($crate::bundles::missing_error(
+                            key,
+                            reason,
+                            Some(locale.clone()),
+                        ));
+                        report.attempts.push($crate::types::FallbackAttempt {
+                            locale: locale.clone(),
+                            reason,
+                        });
+                    }
                 }
-
-                *cell = msg;
             }
             if !has_missing {
-                is_complete = true;
                 break;
             }
         }
 
-        if !is_complete {
-            for (key, _) in $keys
+        for (key, report) in $keys
+            .iter()
+            .zip(&reports)
+            .filter(|(_, report)| report.value.is_none())
+        {
+            let reason = if report
+                .attempts
                 .iter()
-                .zip(&mut result)
-                .filter(|(_, cell)| cell.is_none())
+                .any(|attempt| attempt.reason == $crate::types::FallbackReason::MissingValue)
             {
-                $errors.push(LocalizationError::MissingMessage {
-                    id: key.id.to_string(),
-                    locale: None,
-                });
-            }
+                $crate::types::FallbackReason::MissingValue
+            } else {
+                $crate::types::FallbackReason::MissingMessage
+            };
+            $errors.push($crate::bundles::missing_error(key, reason, None));
         }
 
-        return result;
+        return reports;
     };
 }
 
-impl<G> Bundles<G>
+macro_rules! fill_attributes_from_inner {
+    ($step:expr, $keys:expr, $reports:expr, $errors:expr) => {
+        // The messages which weren't compared with a following locale yet.
+        // Keys requesting specific attributes fell back for them already.
+        let mut pending: Vec<bool> = $keys
+            .iter()
+            .zip($reports.iter())
+            .map(|(key, report)| key.attributes.is_none() && report.value.is_some())
+            .collect();
+
+        while pending.contains(&true) {
+            let bundle = match $step {
+                Some(bundle) => bundle,
+                None => break,
+            };
+            // Errors of the bundle were reported when formatting the messages.
+            let bundle = bundle.as_ref().unwrap_or_else(|(bundle, _)| bundle);
+            let locale = &bundle.locales[0];
+
+            for ((key, report), pending) in $keys
+                .iter()
+                .zip($reports.iter_mut())
+                .zip(&mut pending)
+                .filter(|(_, pending)| **pending)
+            {
+                let (message, source) = match (&mut report.value, &report.locale) {
+                    (Some(message), Some(source)) => (message, source),
+                    _ => continue,
+                };
+                // Other bundles of the locale which provided the message don't
+                // make its translation more complete.
+                if locale == source {
+                    continue;
+                }
+                let msg = match bundle.get_message(&key.id) {
+                    Some(msg) => msg,
+                    None => continue,
+                };
+                // The first following locale with the message is the one
+                // its attributes are taken from.
+                *pending = false;
+
+                for attr in msg.attributes() {
+                    if message.attributes.iter().any(|a| a.name == attr.id()) {
+                        continue;
+                    }
+                    let mut format_errors = vec![];
+                    let attribute =
+                        $crate::bundles::format_attribute(bundle, attr, key, &mut format_errors);
+                    if !format_errors.is_empty() {
+                        $errors.push($crate::LocalizationError::Resolver {
+                            id: key.id.to_string(),
+                            locale: locale.clone(),
+                            errors: format_errors,
+                        });
+                    }
+                    $errors.push($crate::LocalizationError::AttributeFallback {
+                        id: key.id.to_string(),
+                        attribute: attribute.name.to_string(),
+                        locale: source.clone(),
+                        fallback: locale.clone(),
+                    });
+                    message.attributes.push(attribute);
+                }
+            }
+        }
+    };
+}
+
+/// Returns the errors of a generated bundle, if any.
+pub(crate) fn bundle_errors<R, E, M>(
+    bundle: Option<&FluentBundleResult<R, E, M>>,
+) -> Vec<LocalizationError>
+where
+    E: GeneratorError,
+{
+    match bundle {
+        Some(Err((bundle, errors))) => localization_errors(bundle, errors).collect(),
+        _ => vec![],
+    }
+}
+
+/// Converts the errors reported along with `bundle`.
+pub(crate) fn localization_errors<'e, R, E, M>(
+    bundle: &'e bundle::FluentBundle<R, M>,
+    errors: &'e [E],
+) -> impl Iterator<Item = LocalizationError> + 'e
 where
-    G: BundleGenerator,
+    E: GeneratorError,
+{
+    let locale = &bundle.locales[0];
+    errors
+        .iter()
+        .map(move |error| error.to_localization_error(locale))
+}
+
+/// Returns the error for a value which a locale, or no locale at all,
+/// could provide.
+pub(crate) fn missing_error(
+    key: &L10nKey,
+    reason: FallbackReason,
+    locale: Option<LanguageIdentifier>,
+) -> LocalizationError {
+    let id = key.id.to_string();
+    match reason {
+        FallbackReason::MissingValue => LocalizationError::MissingValue { id, locale },
+        _ => LocalizationError::MissingMessage { id, locale },
+    }
+}
+
+pub(crate) fn format_value_for_report<'l, R, M>(
+    bundle: &'l bundle::FluentBundle<R, M>,
+    key: &'l L10nKey,
+    format_errors: &mut Vec<FluentError>,
+) -> Result<Cow<'l, str>, FallbackReason>
+where
+    R: Borrow<FluentResource>,
+    M: MemoizerKind,
+{
+    let msg = bundle
+        .get_message(&key.id)
+        .ok_or(FallbackReason::MissingMessage)?;
+    let value = msg.value().ok_or(FallbackReason::MissingValue)?;
+    Ok(bundle.format_pattern(value, key.args.as_ref(), format_errors))
+}
+
+impl<G, M> Bundles<G, M>
+where
+    G: BundleGenerator<M>,
+    M: LocalizationMemoizer,
+{
+    fn format_values_with_report_from_iter<'l>(
+        segments: &'l [CachedSegment<M, Cache<G::Iter>>],
+        keys: &'l [L10nKey<'l>],
+        errors: &mut Vec<LocalizationError>,
+    ) -> Vec<FallbackReport<Cow<'l, str>>>
+    where
+        G: 'l,
+    {
+        let mut bundle_iter = SegmentsIter::new(segments);
+        format_values_with_report_from_inner!(bundle_iter.next(), keys, errors);
+    }
+
+    async fn format_values_with_report_from_stream<'l>(
+        segments: &'l [CachedSegment<M, AsyncCache<G::Stream>>],
+        keys: &'l [L10nKey<'l>],
+        errors: &mut Vec<LocalizationError>,
+    ) -> Vec<FallbackReport<Cow<'l, str>>>
+    where
+        G: 'l,
+    {
+        let mut bundle_stream = SegmentsStream::new(segments);
+        format_values_with_report_from_inner!(bundle_stream.next().await, keys, errors);
+    }
+}
+
+impl<G, M> Bundles<G, M>
+where
+    G: BundleGenerator<M>,
+    M: LocalizationMemoizer,
 {
     fn format_value_from_iter<'l>(
-        cache: &'l Cache<G::Iter, G::Resource>,
+        segments: &'l [CachedSegment<M, Cache<G::Iter>>],
         id: &'l str,
         args: Option<&'l FluentArgs>,
         errors: &mut Vec<LocalizationError>,
-    ) -> Option<Cow<'l, str>> {
-        let mut bundle_iter = cache.into_iter();
+    ) -> Option<Cow<'l, str>>
+    where
+        G: 'l,
+    {
+        let mut bundle_iter = SegmentsIter::new(segments);
         format_value_from_inner!(bundle_iter.next(), id, args, errors);
     }
 
     async fn format_value_from_stream<'l>(
-        stream: &'l AsyncCache<G::Stream, G::Resource>,
+        segments: &'l [CachedSegment<M, AsyncCache<G::Stream>>],
         id: &'l str,
         args: Option<&'l FluentArgs<'_>>,
         errors: &mut Vec<LocalizationError>,
-    ) -> Option<Cow<'l, str>> {
-        use futures::StreamExt;
-
-        let mut bundle_stream = stream.stream();
+    ) -> Option<Cow<'l, str>>
+    where
+        G: 'l,
+    {
+        let mut bundle_stream = SegmentsStream::new(segments);
         format_value_from_inner!(bundle_stream.next().await, id, args, errors);
     }
 
     async fn format_messages_from_stream<'l>(
-        stream: &'l AsyncCache<G::Stream, G::Resource>,
+        segments: &'l [CachedSegment<M, AsyncCache<G::Stream>>],
         keys: &'l [L10nKey<'l>],
         errors: &mut Vec<LocalizationError>,
-    ) -> Vec<Option<L10nMessage<'l>>> {
-        use futures::StreamExt;
-        let mut bundle_stream = stream.stream();
+    ) -> Vec<FallbackReport<L10nMessage<'l>>>
+    where
+        G: 'l,
+    {
+        let mut bundle_stream = SegmentsStream::new(segments);
         format_messages_from_inner!(bundle_stream.next().await, keys, errors);
     }
 
-    async fn format_values_from_stream<'l>(
-        stream: &'l AsyncCache<G::Stream, G::Resource>,
+    fn format_messages_from_iter<'l>(
+        segments: &'l [CachedSegment<M, Cache<G::Iter>>],
         keys: &'l [L10nKey<'l>],
         errors: &mut Vec<LocalizationError>,
-    ) -> Vec<Option<Cow<'l, str>>> {
-        use futures::StreamExt;
-        let mut bundle_stream = stream.stream();
-
-        format_values_from_inner!(bundle_stream.next().await, keys, errors);
-    }
-
-    fn format_message_from_bundle<'l>(
-        bundle: &'l FluentBundle<G::Resource>,
-        key: &'l L10nKey,
-        format_errors: &mut Vec<FluentError>,
-    ) -> Option<L10nMessage<'l>> {
-        let msg = bundle.get_message(&key.id)?;
-        let value = msg
-            .value()
-            .map(|pattern| bundle.format_pattern(pattern, key.args.as_ref(), format_errors));
-        let attributes = msg
-            .attributes()
-            .map(|attr| {
-                let value = bundle.format_pattern(attr.value(), key.args.as_ref(), format_errors);
-                L10nAttribute {
-                    name: attr.id().into(),
-                    value,
-                }
-            })
-            .collect();
-        Some(L10nMessage { value, attributes })
+    ) -> Vec<FallbackReport<L10nMessage<'l>>>
+    where
+        G: 'l,
+    {
+        let mut bundle_iter = SegmentsIter::new(segments);
+        format_messages_from_inner!(bundle_iter.next(), keys, errors);
     }
 
-    fn format_messages_from_iter<'l>(
-        cache: &'l Cache<G::Iter, G::Resource>,
+    fn fill_attributes_from_iter<'l>(
+        segments: &'l [CachedSegment<M, Cache<G::Iter>>],
         keys: &'l [L10nKey<'l>],
+        reports: &mut [FallbackReport<L10nMessage<'l>>],
         errors: &mut Vec<LocalizationError>,
-    ) -> Vec<Option<L10nMessage<'l>>> {
-        let mut bundle_iter = cache.into_iter();
-        format_messages_from_inner!(bundle_iter.next(), keys, errors);
+    ) where
+        G: 'l,
+    {
+        let mut bundle_iter = SegmentsIter::new(segments);
+        fill_attributes_from_inner!(bundle_iter.next(), keys, reports, errors);
     }
 
-    fn format_values_from_iter<'l>(
-        cache: &'l Cache<G::Iter, G::Resource>,
+    async fn fill_attributes_from_stream<'l>(
+        segments: &'l [CachedSegment<M, AsyncCache<G::Stream>>],
         keys: &'l [L10nKey<'l>],
+        reports: &mut [FallbackReport<L10nMessage<'l>>],
         errors: &mut Vec<LocalizationError>,
-    ) -> Vec<Option<Cow<'l, str>>> {
-        let mut bundle_iter = cache.into_iter();
-        format_values_from_inner!(bundle_iter.next(), keys, errors);
+    ) where
+        G: 'l,
+    {
+        let mut bundle_stream = SegmentsStream::new(segments);
+        fill_attributes_from_inner!(bundle_stream.next().await, keys, reports, errors);
+    }
+}
+
+/// Formats the message of `key` from `bundle`, with the requested attributes
+/// which it has, or all of them.
+pub(crate) fn format_message_from_bundle<'l, R, M>(
+    bundle: &'l bundle::FluentBundle<R, M>,
+    key: &'l L10nKey,
+    format_errors: &mut Vec<FluentError>,
+) -> Option<L10nMessage<'l>>
+where
+    R: Borrow<FluentResource>,
+    M: MemoizerKind,
+{
+    let msg = bundle.get_message(&key.id)?;
+    Some(format_message(bundle, &msg, key, format_errors))
+}
+
+pub(crate) fn format_message<'l, R, M>(
+    bundle: &'l bundle::FluentBundle<R, M>,
+    msg: &FluentMessage<'l>,
+    key: &'l L10nKey,
+    format_errors: &mut Vec<FluentError>,
+) -> L10nMessage<'l>
+where
+    R: Borrow<FluentResource>,
+    M: MemoizerKind,
+{
+    let value = msg
+        .value()
+        .map(|pattern| bundle.format_pattern(pattern, key.args.as_ref(), format_errors));
+    let attributes = match &key.attributes {
+        Some(names) => names
+            .iter()
+            .filter_map(|name| msg.get_attribute(name))
+            .map(|attr| format_attribute(bundle, attr, key, format_errors))
+            .collect(),
+        None => msg
+            .attributes()
+            .map(|attr| format_attribute(bundle, attr, key, format_errors))
+            .collect(),
+    };
+    L10nMessage { value, attributes }
+}
+
+pub(crate) fn format_attribute<'l, R, M>(
+    bundle: &'l bundle::FluentBundle<R, M>,
+    attr: FluentAttribute<'l>,
+    key: &'l L10nKey,
+    format_errors: &mut Vec<FluentError>,
+) -> L10nAttribute<'l>
+where
+    R: Borrow<FluentResource>,
+    M: MemoizerKind,
+{
+    L10nAttribute {
+        name: attr.id().into(),
+        value: bundle.format_pattern(attr.value(), key.args.as_ref(), format_errors),
     }
 }
+
+/// Returns the attributes requested by `key` which `msg` lacks.
+pub(crate) fn missing_attributes<'k>(msg: &FluentMessage, key: &'k L10nKey) -> Vec<&'k str> {
+    key.attributes
+        .iter()
+        .flatten()
+        .filter(|name| msg.get_attribute(name).is_none())
+        .map(|name| name.as_ref())
+        .collect()
+}
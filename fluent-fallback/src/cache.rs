@@ -1,252 +1,236 @@
-use std::{
-    cell::{RefCell, UnsafeCell},
-    cmp::Ordering,
-    pin::Pin,
-    task::Context,
-    task::Poll,
-    task::Waker,
-};
+use std::{ops::Deref, pin::Pin, sync::Mutex};
 
 use crate::generator::{BundleIterator, BundleStream};
-use crate::pin_cell::{PinCell, PinMut};
-use chunky_vec::ChunkyVec;
-use futures::{ready, Stream};
-
-pub struct Cache<I, R>
+use crate::segments::{Batch, BundleCache, GeneratedBundle, Segment, Step, Walk};
+use elsa::sync::FrozenVec;
+use futures::{lock::Mutex as AsyncMutex, Stream, StreamExt};
+use unic_langid::LanguageIdentifier;
+
+/// Generated items, boxed so that references to them stay valid while more
+/// items are pushed.
+type Items<T> = FrozenVec<Box<T>>;
+
+/// Caches the items of an iterator, which is advanced by one thread at a
+/// time, while the items generated so far are read concurrently.
+pub struct Cache<I>
 where
     I: Iterator,
 {
-    iter: RefCell<I>,
-    items: UnsafeCell<ChunkyVec<I::Item>>,
-    res: std::marker::PhantomData<R>,
+    // `None` once the iterator is exhausted.
+    iter: Mutex<Option<I>>,
+    items: Items<I::Item>,
 }
 
-impl<I, R> Cache<I, R>
+impl<I> Cache<I>
 where
     I: Iterator,
 {
     pub fn new(iter: I) -> Self {
         Self {
-            iter: RefCell::new(iter),
-            items: Default::default(),
-            res: std::marker::PhantomData,
-        }
-    }
-
-    pub fn len(&self) -> usize {
-        unsafe {
-            let items = self.items.get();
-            (*items).len()
+            iter: Mutex::new(Some(iter)),
+            items: FrozenVec::new(),
         }
     }
 
+    /// Returns the item at `index`, advancing the iterator as needed.
     pub fn get(&self, index: usize) -> Option<&I::Item> {
-        unsafe {
-            let items = self.items.get();
-            (*items).get(index)
+        if let Some(item) = self.items.get(index) {
+            return Some(item);
         }
-    }
-
-    /// Push, immediately getting a reference to the element
-    pub fn push_get(&self, new_value: I::Item) -> &I::Item {
-        unsafe {
-            let items = self.items.get();
-            (*items).push_get(new_value)
+        let mut iter = self.iter.lock().unwrap();
+        // Another thread may have advanced the iterator while this one was waiting.
+        loop {
+            if let Some(item) = self.items.get(index) {
+                return Some(item);
+            }
+            match iter.as_mut()?.next() {
+                Some(item) => self.items.push(Box::new(item)),
+                None => {
+                    *iter = None;
+                    return None;
+                }
+            }
         }
     }
 }
 
-impl<I, R> Cache<I, R>
+impl<I> Cache<I>
 where
     I: BundleIterator + Iterator,
 {
     pub fn prefetch(&self) {
-        self.iter.borrow_mut().prefetch_sync();
+        if let Some(iter) = self.iter.lock().unwrap().as_mut() {
+            iter.prefetch_sync();
+        }
     }
 }
 
-pub struct CacheIter<'a, I, R>
+impl<I> BundleCache for Cache<I>
 where
     I: Iterator,
+    I::Item: GeneratedBundle,
 {
-    cache: &'a Cache<I, R>,
-    curr: usize,
+    fn generated_locales(&self) -> Vec<&LanguageIdentifier> {
+        (0..)
+            .map_while(|index| self.items.get(index))
+            .filter_map(GeneratedBundle::locale)
+            .collect()
+    }
 }
 
-impl<'a, I, R> Iterator for CacheIter<'a, I, R>
-where
-    I: Iterator,
-{
-    type Item = &'a I::Item;
+/// Iterates the bundles of the segments of cached batches, shared with `B`.
+pub(crate) struct SegmentsIter<'a, B> {
+    segments: std::slice::Iter<'a, Segment<B>>,
+    current: Option<Walk<'a, B, usize>>,
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let cache_len = self.cache.len();
-        match self.curr.cmp(&cache_len) {
-            Ordering::Less => {
-                // Cached value
-                self.curr += 1;
-                self.cache.get(self.curr - 1)
-            }
-            Ordering::Equal => {
-                // Get the next item from the iterator
-                let item = self.cache.iter.borrow_mut().next();
-                self.curr += 1;
-                if let Some(item) = item {
-                    Some(self.cache.push_get(item))
-                } else {
-                    None
-                }
-            }
-            Ordering::Greater => {
-                // Ran off the end of the cache
-                None
-            }
+impl<'a, B> SegmentsIter<'a, B> {
+    pub(crate) fn new(segments: &'a [Segment<B>]) -> Self {
+        Self {
+            segments: segments.iter(),
+            current: None,
         }
     }
 }
 
-impl<'a, I, R> IntoIterator for &'a Cache<I, R>
+impl<'a, B, I> Iterator for SegmentsIter<'a, B>
 where
-    I: Iterator,
+    B: Deref<Target = Batch<Cache<I>>>,
+    I: Iterator + 'a,
+    I::Item: GeneratedBundle,
 {
     type Item = &'a I::Item;
-    type IntoIter = CacheIter<'a, I, R>;
 
-    fn into_iter(self) -> Self::IntoIter {
-        CacheIter {
-            cache: self,
-            curr: 0,
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let walk = match &mut self.current {
+                Some(walk) => walk,
+                None => self.current.insert(Walk::new(self.segments.next()?, 0)),
+            };
+            let segment: &'a Segment<B> = walk.segment;
+            match segment.batch.cache.get(walk.cursor) {
+                Some(bundle) => {
+                    walk.cursor += 1;
+                    match walk.step(bundle.locale()) {
+                        Step::Skip => {}
+                        Step::Take => return Some(bundle),
+                        Step::End => self.current = None,
+                    }
+                }
+                None => self.current = None,
+            }
         }
     }
 }
 
 ////////////////////////////////////////////////////////////////////////////////
 
-pub struct AsyncCache<S, R>
+/// The asynchronous counterpart of [`Cache`], polling a stream instead.
+pub struct AsyncCache<S>
 where
     S: Stream,
 {
-    stream: PinCell<S>,
-    items: UnsafeCell<ChunkyVec<S::Item>>,
-    // TODO: Should probably be an SmallVec<[Waker; 1]> or something? I guess
-    // multiple pending wakes are not really all that common.
-    pending_wakes: RefCell<Vec<Waker>>,
-    res: std::marker::PhantomData<R>,
+    // `None` once the stream is exhausted.
+    stream: AsyncMutex<Option<Pin<Box<S>>>>,
+    items: Items<S::Item>,
 }
 
-impl<S, R> AsyncCache<S, R>
+impl<S> AsyncCache<S>
 where
     S: Stream,
 {
     pub fn new(stream: S) -> Self {
         Self {
-            stream: PinCell::new(stream),
-            items: Default::default(),
-            pending_wakes: Default::default(),
-            res: std::marker::PhantomData,
+            stream: AsyncMutex::new(Some(Box::pin(stream))),
+            items: FrozenVec::new(),
         }
     }
 
-    pub fn len(&self) -> usize {
-        unsafe {
-            let items = self.items.get();
-            (*items).len()
+    /// Returns the item at `index`, polling the stream as needed.
+    pub async fn get(&self, index: usize) -> Option<&S::Item> {
+        if let Some(item) = self.items.get(index) {
+            return Some(item);
         }
-    }
-
-    pub fn get(&self, index: usize) -> Poll<Option<&S::Item>> {
-        unsafe {
-            let items = self.items.get();
-            (*items).get(index).into()
-        }
-    }
-
-    /// Push, immediately getting a reference to the element
-    pub fn push_get(&self, new_value: S::Item) -> &S::Item {
-        unsafe {
-            let items = self.items.get();
-            (*items).push_get(new_value)
-        }
-    }
-
-    pub fn stream(&self) -> AsyncCacheStream<'_, S, R> {
-        AsyncCacheStream {
-            cache: self,
-            curr: 0,
+        let mut stream = self.stream.lock().await;
+        // Another task may have polled the stream while this one was waiting.
+        loop {
+            if let Some(item) = self.items.get(index) {
+                return Some(item);
+            }
+            match stream.as_mut()?.next().await {
+                Some(item) => self.items.push(Box::new(item)),
+                None => {
+                    *stream = None;
+                    return None;
+                }
+            }
         }
     }
 }
 
-impl<S, R> AsyncCache<S, R>
+impl<S> AsyncCache<S>
 where
     S: BundleStream + Stream,
 {
     pub async fn prefetch(&self) {
-        let pin = unsafe { Pin::new_unchecked(&self.stream) };
-        unsafe { PinMut::as_mut(&mut pin.borrow_mut()).get_unchecked_mut() }
-            .prefetch_async()
-            .await
+        if let Some(stream) = self.stream.lock().await.as_mut() {
+            // `prefetch_async` only advances the stream in place, like polling it.
+            unsafe { stream.as_mut().get_unchecked_mut() }
+                .prefetch_async()
+                .await
+        }
     }
 }
 
-impl<S, R> AsyncCache<S, R>
+impl<S> BundleCache for AsyncCache<S>
 where
     S: Stream,
+    S::Item: GeneratedBundle,
 {
-    // Helper function that gets the next value from wrapped stream.
-    fn poll_next_item(&self, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
-        let pin = unsafe { Pin::new_unchecked(&self.stream) };
-        let poll = PinMut::as_mut(&mut pin.borrow_mut()).poll_next(cx);
-        if poll.is_ready() {
-            let wakers = std::mem::take(&mut *self.pending_wakes.borrow_mut());
-            for waker in wakers {
-                waker.wake();
-            }
-        } else {
-            self.pending_wakes.borrow_mut().push(cx.waker().clone());
-        }
-        poll
+    fn generated_locales(&self) -> Vec<&LanguageIdentifier> {
+        (0..)
+            .map_while(|index| self.items.get(index))
+            .filter_map(GeneratedBundle::locale)
+            .collect()
     }
 }
 
-pub struct AsyncCacheStream<'a, S, R>
-where
-    S: Stream,
-{
-    cache: &'a AsyncCache<S, R>,
-    curr: usize,
+/// The asynchronous counterpart of [`SegmentsIter`].
+pub(crate) struct SegmentsStream<'a, B> {
+    segments: std::slice::Iter<'a, Segment<B>>,
+    current: Option<Walk<'a, B, usize>>,
 }
 
-impl<'a, S, R> Stream for AsyncCacheStream<'a, S, R>
-where
-    S: Stream,
-{
-    type Item = &'a S::Item;
-
-    fn poll_next(
-        mut self: std::pin::Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
-    ) -> Poll<Option<Self::Item>> {
-        let cache_len = self.cache.len();
-        match self.curr.cmp(&cache_len) {
-            Ordering::Less => {
-                // Cached value
-                self.curr += 1;
-                self.cache.get(self.curr - 1)
-            }
-            Ordering::Equal => {
-                // Get the next item from the stream
-                let item = ready!(self.cache.poll_next_item(cx));
-                self.curr += 1;
-                if let Some(item) = item {
-                    Some(self.cache.push_get(item)).into()
-                } else {
-                    None.into()
+impl<'a, B> SegmentsStream<'a, B> {
+    pub(crate) fn new(segments: &'a [Segment<B>]) -> Self {
+        Self {
+            segments: segments.iter(),
+            current: None,
+        }
+    }
+
+    pub(crate) async fn next<S>(&mut self) -> Option<&'a S::Item>
+    where
+        B: Deref<Target = Batch<AsyncCache<S>>>,
+        S: Stream + 'a,
+        S::Item: GeneratedBundle,
+    {
+        loop {
+            let walk = match &mut self.current {
+                Some(walk) => walk,
+                None => self.current.insert(Walk::new(self.segments.next()?, 0)),
+            };
+            let segment: &'a Segment<B> = walk.segment;
+            match segment.batch.cache.get(walk.cursor).await {
+                Some(bundle) => {
+                    walk.cursor += 1;
+                    match walk.step(bundle.locale()) {
+                        Step::Skip => {}
+                        Step::Take => return Some(bundle),
+                        Step::End => self.current = None,
+                    }
                 }
-            }
-            Ordering::Greater => {
-                // Ran off the end of the cache
-                None.into()
+                None => self.current = None,
             }
         }
     }
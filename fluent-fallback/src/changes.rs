@@ -0,0 +1,92 @@
+use crate::types::ResourceId;
+
+/// A change of a [`Localization`](crate::Localization), after which the
+/// translations depending on it should be formatted again.
+///
+/// # Example
+///
+/// ```
+/// use fluent_fallback::{Localization, LocalizationChange};
+/// use fluent_resmgr::ResourceManager;
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+/// use unic_langid::langid;
+///
+/// let res_mgr = ResourceManager::new("./tests/resources/{locale}/{res_id}".to_string());
+/// let mut loc = Localization::with_env(
+///     vec!["test.ftl".into()],
+///     true,
+///     vec![langid!("en-US")],
+///     res_mgr,
+/// );
+///
+/// let changes = Rc::new(RefCell::new(vec![]));
+/// let observer = changes.clone();
+/// loc.subscribe(move |change: &LocalizationChange| observer.borrow_mut().push(change.clone()));
+///
+/// loc.add_resource_id("test2.ftl");
+/// assert_eq!(
+///     *changes.borrow(),
+///     vec![LocalizationChange::ResourcesAdded(vec!["test2.ftl".into()])]
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum LocalizationChange {
+    /// The locales of the [`LocalesProvider`](crate::env::LocalesProvider)
+    /// changed, which is detected by [`Localization::update_provider`](crate::Localization::update_provider)
+    /// and [`Localization::on_change`](crate::Localization::on_change).
+    Locales,
+    /// The resources were added, or their type changed.
+    ResourcesAdded(Vec<ResourceId>),
+    /// The resources were removed.
+    ResourcesRemoved(Vec<ResourceId>),
+    /// The generator reloaded some of its resources.
+    ResourcesReloaded,
+}
+
+/// Identifies an observer subscribed with
+/// [`Localization::subscribe`](crate::Localization::subscribe).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(usize);
+
+/// The observers of the changes of a [`Localization`](crate::Localization).
+///
+/// `O` is `dyn Fn(&LocalizationChange) + Send + Sync` for the concurrent
+/// localization.
+pub(crate) struct Observers<O: ?Sized = dyn Fn(&LocalizationChange)> {
+    next_id: usize,
+    observers: Vec<(SubscriptionId, Box<O>)>,
+}
+
+impl<O: ?Sized> Default for Observers<O> {
+    fn default() -> Self {
+        Self {
+            next_id: 0,
+            observers: vec![],
+        }
+    }
+}
+
+impl<O> Observers<O>
+where
+    O: Fn(&LocalizationChange) + ?Sized,
+{
+    pub fn subscribe(&mut self, observer: Box<O>) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id);
+        self.next_id += 1;
+        self.observers.push((id, observer));
+        id
+    }
+
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let len = self.observers.len();
+        self.observers.retain(|(observer_id, _)| *observer_id != id);
+        self.observers.len() != len
+    }
+
+    pub fn notify(&self, change: &LocalizationChange) {
+        for (_, observer) in &self.observers {
+            observer(change);
+        }
+    }
+}
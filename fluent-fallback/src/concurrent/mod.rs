@@ -0,0 +1,113 @@
+//! Thread-safe specialization of [`Localization`](crate::Localization).
+//!
+//! [`Localization`] in this module works with bundles over the concurrent
+//! [`IntlLangMemoizer`], generated by a [`BundleGenerator<IntlLangMemoizer>`](crate::generator::BundleGenerator),
+//! and shares its state with [`Arc`](std::sync::Arc). When the generator,
+//! its iterators and streams, and the resources are [`Send`] and [`Sync`],
+//! the localization and its [`Bundles`] are [`Send`] and [`Sync`] too, so a
+//! single instance can be shared between threads, and the futures returned
+//! by the asynchronous methods can be spawned on multi-threaded executors,
+//! except the one of [`prefetch_async`](crate::Localization::prefetch_async),
+//! as [`BundleStream::prefetch_async`](crate::generator::BundleStream::prefetch_async)
+//! isn't required to be [`Send`].
+//!
+//! Bundles are generated lazily and cached as in the single-threaded
+//! [`Localization`](crate::Localization). When several threads or tasks
+//! need a bundle which hasn't been generated yet, one of them advances the
+//! generator while the others wait for it.
+//!
+//! The settings, the fallback reports and the [`StrictBundles`] work as in
+//! the single-threaded localization, except that error sinks and observers
+//! have to be [`Send`] and [`Sync`] too.
+//!
+//! # Example
+//!
+//! ```
+//! use fluent_bundle::{concurrent::FluentBundle, FluentError, FluentResource};
+//! use fluent_fallback::{
+//!     concurrent::{FluentBundleResult, IntlLangMemoizer, Localization},
+//!     generator::BundleGenerator,
+//!     types::ResourceId,
+//! };
+//! use futures::stream::{self, Iter};
+//! use std::sync::Arc;
+//! use std::thread;
+//! use std::vec;
+//! use unic_langid::{langid, LanguageIdentifier};
+//!
+//! struct Generator;
+//!
+//! impl Generator {
+//!     fn generate(&self, locales: vec::IntoIter<LanguageIdentifier>) -> Vec<FluentBundleResult<FluentResource>> {
+//!         locales
+//!             .map(|locale| {
+//!                 let res = FluentResource::try_new("hello = Hello!".to_string())
+//!                     .expect("Failed to parse a resource.");
+//!                 let mut bundle = FluentBundle::new_concurrent(vec![locale]);
+//!                 bundle.add_resource(res).expect("Failed to add a resource.");
+//!                 Ok(bundle)
+//!             })
+//!             .collect()
+//!     }
+//! }
+//!
+//! impl BundleGenerator<IntlLangMemoizer> for Generator {
+//!     type Resource = FluentResource;
+//!     type Error = FluentError;
+//!     type LocalesIter = vec::IntoIter<LanguageIdentifier>;
+//!     type Iter = vec::IntoIter<FluentBundleResult<FluentResource>>;
+//!     type Stream = Iter<Self::Iter>;
+//!
+//!     fn bundles_iter(&self, locales: Self::LocalesIter, _: Vec<ResourceId>) -> Self::Iter {
+//!         self.generate(locales).into_iter()
+//!     }
+//!
+//!     fn bundles_stream(&self, locales: Self::LocalesIter, _: Vec<ResourceId>) -> Self::Stream {
+//!         stream::iter(self.generate(locales).into_iter())
+//!     }
+//! }
+//!
+//! let loc = Arc::new(Localization::with_env(
+//!     vec!["main.ftl".into()],
+//!     true,
+//!     vec![langid!("en-US")],
+//!     Generator,
+//! ));
+//!
+//! let handles: Vec<_> = (0..4)
+//!     .map(|_| {
+//!         let loc = Arc::clone(&loc);
+//!         thread::spawn(move || {
+//!             let mut errors = vec![];
+//!             loc.bundles()
+//!                 .format_value_sync("hello", None, &mut errors)
+//!                 .expect("Failed to format a value.")
+//!                 .map(|value| value.to_string())
+//!         })
+//!     })
+//!     .collect();
+//!
+//! for handle in handles {
+//!     assert_eq!(handle.join().unwrap().as_deref(), Some("Hello!"));
+//! }
+//! ```
+use fluent_bundle::{concurrent::FluentBundle, FluentError, FluentResource};
+
+pub use intl_memoizer::concurrent::IntlLangMemoizer;
+
+/// The thread-safe [`Localization`](crate::Localization).
+pub type Localization<G, P> = crate::Localization<G, P, IntlLangMemoizer>;
+
+/// The bundles of the thread-safe [`Localization`].
+pub type Bundles<G> = crate::Bundles<G, IntlLangMemoizer>;
+
+/// The strict view of the bundles of the thread-safe [`Localization`].
+pub type StrictBundles<'b, G> = crate::StrictBundles<'b, G, IntlLangMemoizer>;
+
+/// A bundle generated for the thread-safe [`Localization`].
+pub type FluentBundleResult<R, E = FluentError> =
+    crate::generator::FluentBundleResult<R, E, IntlLangMemoizer>;
+
+/// The thread-safe counterpart of [`LastResort`](crate::LastResort), whose
+/// embedded bundle uses the concurrent memoizer.
+pub type LastResort = crate::LastResort<FluentBundle<FluentResource>>;
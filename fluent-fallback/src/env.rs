@@ -2,8 +2,10 @@
 //!
 //! Since [`Localization`](crate::Localization) is a long-lived structure,
 //! the model in which the user provides ability for the system to react to changes
-//! is by implementing the given environmental trait and triggering
-//! [`Localization::on_change`](crate::Localization::on_change) method.
+//! is by implementing the given environmental trait and either updating it through
+//! [`Localization::update_provider`](crate::Localization::update_provider), or
+//! triggering [`Localization::on_change`](crate::Localization::on_change) method
+//! after updating it elsewhere.
 //!
 //! At the moment just a single trait is provided, which allows the
 //! environment to feed a selection of locales to be provided to the instance.
@@ -12,7 +14,10 @@
 //! already negotiated to ensure that the resources in those locales
 //! are available. The list should also be sorted according to the user
 //! preference, as the order is significant for how [`Localization`](crate::Localization) performs
-//! fallbacking.
+//! fallbacking. [`NegotiatedLocales`] takes care of both.
+use fluent_langneg::negotiate_languages;
+pub use fluent_langneg::NegotiationStrategy;
+use std::sync::{Arc, RwLock};
 use unic_langid::LanguageIdentifier;
 
 /// A trait used to provide a selection of locales to be used by the
@@ -53,7 +58,7 @@ use unic_langid::LanguageIdentifier;
 ///     }
 /// }
 ///
-/// let res_mgr = ResourceManager::new("./path/{locale}/".to_string());
+/// let res_mgr = ResourceManager::new("./path/{locale}/{res_id}".to_string());
 ///
 /// let mut env = Env::new(vec![
 ///     "en-GB".parse().unwrap()
@@ -74,6 +79,18 @@ use unic_langid::LanguageIdentifier;
 pub trait LocalesProvider {
     type Iter: Iterator<Item = LanguageIdentifier>;
     fn locales(&self) -> Self::Iter;
+
+    /// Returns an iterator over `locales`, some of the locales of the
+    /// provider, in order.
+    ///
+    /// When the locales change, [`Localization`](crate::Localization) uses
+    /// it to generate only the bundles of the locales which were added, and
+    /// keeps the bundles of the others. Without it, which is the default,
+    /// the bundles of the other locales are still kept, but the ones to
+    /// generate come from all the locales.
+    fn locales_subset(&self, _locales: Vec<LanguageIdentifier>) -> Option<Self::Iter> {
+        None
+    }
 }
 
 impl LocalesProvider for Vec<LanguageIdentifier> {
@@ -81,4 +98,148 @@ impl LocalesProvider for Vec<LanguageIdentifier> {
     fn locales(&self) -> Self::Iter {
         self.clone().into_iter()
     }
+
+    fn locales_subset(&self, locales: Vec<LanguageIdentifier>) -> Option<Self::Iter> {
+        Some(locales.into_iter())
+    }
+}
+
+struct Negotiation {
+    requested: Vec<LanguageIdentifier>,
+    available: Vec<LanguageIdentifier>,
+    default: Option<LanguageIdentifier>,
+    strategy: NegotiationStrategy,
+    negotiated: Vec<LanguageIdentifier>,
+}
+
+impl Negotiation {
+    /// Negotiates the locales again, returning `true` if they changed.
+    fn negotiate(&mut self) -> bool {
+        let negotiated: Vec<LanguageIdentifier> = negotiate_languages(
+            &self.requested,
+            &self.available,
+            self.default.as_ref(),
+            self.strategy,
+        )
+        .into_iter()
+        .cloned()
+        .collect();
+        let changed = negotiated != self.negotiated;
+        self.negotiated = negotiated;
+        changed
+    }
+}
+
+/// A [`LocalesProvider`] negotiating the locales requested by the user
+/// against the locales for which resources are available.
+///
+/// The locales are negotiated again on every update, and the setters return
+/// `true` if the result changed. Updating the provider through
+/// [`Localization::update_provider`](crate::Localization::update_provider)
+/// invalidates the bundles of the localization and notifies its observers
+/// in that case.
+///
+/// Clones share the same state, so one of them can also be kept to update
+/// the locales elsewhere, after which
+/// [`Localization::on_change`](crate::Localization::on_change) has to be
+/// called.
+///
+/// # Example
+///
+/// ```
+/// use fluent_fallback::{
+///     env::{LocalesProvider, NegotiatedLocales, NegotiationStrategy},
+///     Localization, LocalizationChange,
+/// };
+/// use fluent_resmgr::ResourceManager;
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+/// use unic_langid::langid;
+///
+/// let provider = NegotiatedLocales::new(
+///     vec![langid!("de-AT"), langid!("fr")],
+///     vec![langid!("de"), langid!("en-US"), langid!("pl")],
+///     Some(langid!("en-US")),
+///     NegotiationStrategy::Filtering,
+/// );
+/// assert_eq!(
+///     provider.locales().collect::<Vec<_>>(),
+///     vec![langid!("de"), langid!("en-US")],
+/// );
+///
+/// let res_mgr = ResourceManager::new("./tests/resources/{locale}/{res_id}".to_string());
+/// let mut loc = Localization::with_env(vec!["test.ftl".into()], true, provider, res_mgr);
+///
+/// let changed = Rc::new(Cell::new(false));
+/// let observer = changed.clone();
+/// loc.subscribe(move |change: &LocalizationChange| {
+///     observer.set(*change == LocalizationChange::Locales)
+/// });
+///
+/// assert!(loc.update_provider(|provider| provider.set_requested(vec![langid!("pl")])));
+/// assert_eq!(
+///     loc.provider().locales().collect::<Vec<_>>(),
+///     vec![langid!("pl"), langid!("en-US")],
+/// );
+/// assert!(changed.get());
+/// ```
+#[derive(Clone)]
+pub struct NegotiatedLocales {
+    inner: Arc<RwLock<Negotiation>>,
+}
+
+impl NegotiatedLocales {
+    pub fn new(
+        requested: Vec<LanguageIdentifier>,
+        available: Vec<LanguageIdentifier>,
+        default: Option<LanguageIdentifier>,
+        strategy: NegotiationStrategy,
+    ) -> Self {
+        let mut negotiation = Negotiation {
+            requested,
+            available,
+            default,
+            strategy,
+            negotiated: vec![],
+        };
+        negotiation.negotiate();
+        Self {
+            inner: Arc::new(RwLock::new(negotiation)),
+        }
+    }
+
+    pub fn requested(&self) -> Vec<LanguageIdentifier> {
+        self.inner.read().unwrap().requested.clone()
+    }
+
+    pub fn available(&self) -> Vec<LanguageIdentifier> {
+        self.inner.read().unwrap().available.clone()
+    }
+
+    /// Sets the locales requested by the user, in order of preference.
+    /// Returns `true` if the negotiated locales changed.
+    pub fn set_requested(&self, requested: Vec<LanguageIdentifier>) -> bool {
+        let mut inner = self.inner.write().unwrap();
+        inner.requested = requested;
+        inner.negotiate()
+    }
+
+    /// Sets the locales for which resources are available.
+    /// Returns `true` if the negotiated locales changed.
+    pub fn set_available(&self, available: Vec<LanguageIdentifier>) -> bool {
+        let mut inner = self.inner.write().unwrap();
+        inner.available = available;
+        inner.negotiate()
+    }
+}
+
+impl LocalesProvider for NegotiatedLocales {
+    type Iter = <Vec<LanguageIdentifier> as IntoIterator>::IntoIter;
+    fn locales(&self) -> Self::Iter {
+        self.inner.read().unwrap().negotiated.clone().into_iter()
+    }
+
+    fn locales_subset(&self, locales: Vec<LanguageIdentifier>) -> Option<Self::Iter> {
+        Some(locales.into_iter())
+    }
 }
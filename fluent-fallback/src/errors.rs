@@ -1,12 +1,25 @@
 use fluent_bundle::FluentError;
+use std::collections::HashSet;
 use std::error::Error;
+use std::io;
+use std::sync::Mutex;
 use unic_langid::LanguageIdentifier;
 
 #[derive(Debug, PartialEq)]
 pub enum LocalizationError {
+    /// An error of the bundle of `locale`, such as one of parsing the
+    /// resource `res_id`.
     Bundle {
+        locale: LanguageIdentifier,
+        res_id: Option<String>,
         error: FluentError,
     },
+    /// A resource of the bundle of `locale` could not be loaded.
+    ResourceLoad {
+        id: String,
+        locale: LanguageIdentifier,
+        kind: io::ErrorKind,
+    },
     Resolver {
         id: String,
         locale: LanguageIdentifier,
@@ -20,19 +33,44 @@ pub enum LocalizationError {
         id: String,
         locale: Option<LanguageIdentifier>,
     },
+    MissingAttribute {
+        id: String,
+        attribute: String,
+        locale: LanguageIdentifier,
+    },
+    /// The message of `locale` lacks an attribute, which was taken from the
+    /// message of the `fallback` locale instead.
+    AttributeFallback {
+        id: String,
+        attribute: String,
+        locale: LanguageIdentifier,
+        fallback: LanguageIdentifier,
+    },
     SyncRequestInAsyncMode,
 }
 
-impl From<FluentError> for LocalizationError {
-    fn from(error: FluentError) -> Self {
-        Self::Bundle { error }
-    }
-}
-
 impl std::fmt::Display for LocalizationError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Bundle { error } => write!(f, "[fluent][bundle] error: {}", error),
+            Self::Bundle {
+                locale,
+                res_id: Some(res_id),
+                error,
+            } => write!(
+                f,
+                "[fluent][bundle] error in {}/{}: {}",
+                locale, res_id, error
+            ),
+            Self::Bundle {
+                locale,
+                res_id: None,
+                error,
+            } => write!(f, "[fluent][bundle] error in {}: {}", locale, error),
+            Self::ResourceLoad { id, locale, kind } => write!(
+                f,
+                "[fluent] Failed to load resource in locale {}: {}: {}",
+                locale, id, kind
+            ),
             Self::Resolver { id, locale, errors } => {
                 let errors: Vec<String> = errors.iter().map(|err| err.to_string()).collect();
                 write!(
@@ -61,6 +99,25 @@ impl std::fmt::Display for LocalizationError {
             Self::MissingValue { id, locale: None } => {
                 write!(f, "[fluent] Couldn't find a message with value: {}", id)
             }
+            Self::MissingAttribute {
+                id,
+                attribute,
+                locale,
+            } => write!(
+                f,
+                "[fluent] Missing attribute in locale {}: {}.{}",
+                locale, id, attribute
+            ),
+            Self::AttributeFallback {
+                id,
+                attribute,
+                locale,
+                fallback,
+            } => write!(
+                f,
+                "[fluent] Missing attribute in locale {}, using locale {}: {}.{}",
+                locale, fallback, id, attribute
+            ),
             Self::SyncRequestInAsyncMode => {
                 write!(f, "Triggered synchronous format while in async mode")
             }
@@ -69,3 +126,152 @@ impl std::fmt::Display for LocalizationError {
 }
 
 impl Error for LocalizationError {}
+
+/// Receives the errors encountered by a [`Localization`](crate::Localization),
+/// for example to forward them to logs or metrics.
+///
+/// Every error is reported once per [`Localization`](crate::Localization)
+/// lifetime, identified by its locale, message id and kind, while the
+/// `errors` vectors passed to the formatting methods still receive all of them.
+///
+/// It's implemented for closures taking a [`LocalizationError`].
+///
+/// # Example
+///
+/// ```
+/// use fluent_fallback::Localization;
+/// use fluent_resmgr::ResourceManager;
+/// use unic_langid::langid;
+///
+/// let res_mgr = ResourceManager::new("./tests/resources/{locale}/{res_id}".to_string());
+/// let mut loc = Localization::with_env(
+///     vec!["test.ftl".into()],
+///     true,
+///     vec![langid!("en-US")],
+///     res_mgr,
+/// );
+/// loc.set_error_sink(|error: &_| eprintln!("{}", error));
+/// ```
+pub trait ErrorSink {
+    fn report(&self, error: &LocalizationError);
+}
+
+impl<F> ErrorSink for F
+where
+    F: Fn(&LocalizationError),
+{
+    fn report(&self, error: &LocalizationError) {
+        self(error)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum ErrorKind {
+    Bundle,
+    ResourceLoad,
+    Resolver,
+    MissingMessage,
+    MissingValue,
+    MissingAttribute,
+    AttributeFallback,
+    SyncRequestInAsyncMode,
+}
+
+type ErrorKey = (Option<LanguageIdentifier>, String, ErrorKind);
+
+fn get_key(error: &LocalizationError) -> ErrorKey {
+    match error {
+        // Bundle errors aren't tied to a message, so they're told apart by
+        // their resource and description.
+        LocalizationError::Bundle {
+            locale,
+            res_id,
+            error,
+        } => (
+            Some(locale.clone()),
+            format!("{}: {}", res_id.as_deref().unwrap_or_default(), error),
+            ErrorKind::Bundle,
+        ),
+        LocalizationError::ResourceLoad { id, locale, .. } => {
+            (Some(locale.clone()), id.clone(), ErrorKind::ResourceLoad)
+        }
+        LocalizationError::Resolver { id, locale, .. } => {
+            (Some(locale.clone()), id.clone(), ErrorKind::Resolver)
+        }
+        LocalizationError::MissingMessage { id, locale } => {
+            (locale.clone(), id.clone(), ErrorKind::MissingMessage)
+        }
+        LocalizationError::MissingValue { id, locale } => {
+            (locale.clone(), id.clone(), ErrorKind::MissingValue)
+        }
+        LocalizationError::MissingAttribute {
+            id,
+            attribute,
+            locale,
+        } => (
+            Some(locale.clone()),
+            format!("{}.{}", id, attribute),
+            ErrorKind::MissingAttribute,
+        ),
+        LocalizationError::AttributeFallback {
+            id,
+            attribute,
+            locale,
+            ..
+        } => (
+            Some(locale.clone()),
+            format!("{}.{}", id, attribute),
+            ErrorKind::AttributeFallback,
+        ),
+        LocalizationError::SyncRequestInAsyncMode => {
+            (None, String::new(), ErrorKind::SyncRequestInAsyncMode)
+        }
+    }
+}
+
+/// Forwards errors to an [`ErrorSink`], skipping the ones already reported.
+///
+/// `S` is `dyn ErrorSink + Send + Sync` for the concurrent localization.
+pub(crate) struct ErrorReporter<S: ?Sized = dyn ErrorSink> {
+    sink: Option<Box<S>>,
+    reported: Mutex<HashSet<ErrorKey>>,
+}
+
+impl<S: ?Sized> Default for ErrorReporter<S> {
+    fn default() -> Self {
+        Self {
+            sink: None,
+            reported: Default::default(),
+        }
+    }
+}
+
+impl<S> ErrorReporter<S>
+where
+    S: ErrorSink + ?Sized,
+{
+    pub fn new(sink: Box<S>) -> Self {
+        Self {
+            sink: Some(sink),
+            reported: Default::default(),
+        }
+    }
+
+    pub fn report(&self, errors: &[LocalizationError]) {
+        let sink = match &self.sink {
+            Some(sink) => sink,
+            None => return,
+        };
+        // Collect first, so the sink may use the localization reentrantly.
+        let new_errors: Vec<_> = {
+            let mut reported = self.reported.lock().unwrap();
+            errors
+                .iter()
+                .filter(|error| reported.insert(get_key(error)))
+                .collect()
+        };
+        for error in new_errors {
+            sink.report(error);
+        }
+    }
+}
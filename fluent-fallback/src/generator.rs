@@ -1,11 +1,17 @@
-use fluent_bundle::{FluentBundle, FluentError, FluentResource};
+use fluent_bundle::{bundle::FluentBundle, FluentError, FluentResource};
 use futures::Stream;
+use intl_memoizer::IntlLangMemoizer;
 use std::borrow::Borrow;
+use std::pin::Pin;
+use std::task::{Context, Poll};
 use unic_langid::LanguageIdentifier;
 
-use crate::types::ResourceId;
+use crate::{types::ResourceId, LocalizationError};
 
-pub type FluentBundleResult<R> = Result<FluentBundle<R>, (FluentBundle<R>, Vec<FluentError>)>;
+/// A generated bundle, or the bundle along with the errors encountered while
+/// generating it, such as the [`FluentError`]s of adding its resources.
+pub type FluentBundleResult<R, E = FluentError, M = IntlLangMemoizer> =
+    Result<FluentBundle<R, M>, (FluentBundle<R, M>, Vec<E>)>;
 
 pub trait BundleIterator {
     fn prefetch_sync(&mut self) {}
@@ -16,21 +22,111 @@ pub trait BundleStream {
     async fn prefetch_async(&mut self) {}
 }
 
-pub trait BundleGenerator {
+/// An error reported along with a generated bundle, see [`BundleGenerator::Error`].
+pub trait GeneratorError {
+    /// Returns the [`LocalizationError`] for this error of the bundle of `locale`.
+    fn to_localization_error(&self, locale: &LanguageIdentifier) -> LocalizationError;
+}
+
+impl GeneratorError for FluentError {
+    fn to_localization_error(&self, locale: &LanguageIdentifier) -> LocalizationError {
+        LocalizationError::Bundle {
+            locale: locale.clone(),
+            res_id: None,
+            error: self.clone(),
+        }
+    }
+}
+
+/// Generates the bundles used by a [`Localization`](crate::Localization),
+/// one per locale, in fallback order.
+///
+/// A generator which only loads bundles synchronously can still be used in
+/// async mode by wrapping its iterator in an [`IterStream`]:
+///
+/// ```ignore
+/// type Stream = IterStream<Self::Iter>;
+///
+/// fn bundles_stream(&self, locales: Self::LocalesIter, res_ids: Vec<ResourceId>) -> Self::Stream {
+///     self.bundles_iter(locales, res_ids).into()
+/// }
+/// ```
+///
+/// The errors of the bundles are of the generator's own [`BundleGenerator::Error`]
+/// type, for example to report resources which could not be loaded, and are
+/// converted into [`LocalizationError`]s by the [`Localization`](crate::Localization).
+///
+/// The bundles use the memoizer `M`, which is the concurrent
+/// [`IntlLangMemoizer`](intl_memoizer::concurrent::IntlLangMemoizer) for the
+/// generators of a [`concurrent::Localization`](crate::concurrent::Localization).
+pub trait BundleGenerator<M = IntlLangMemoizer> {
     type Resource: Borrow<FluentResource>;
+    type Error: GeneratorError;
     type LocalesIter: Iterator<Item = LanguageIdentifier>;
-    type Iter: Iterator<Item = FluentBundleResult<Self::Resource>>;
-    type Stream: Stream<Item = FluentBundleResult<Self::Resource>>;
+    type Iter: Iterator<Item = FluentBundleResult<Self::Resource, Self::Error, M>>;
+    type Stream: Stream<Item = FluentBundleResult<Self::Resource, Self::Error, M>>;
+
+    fn bundles_iter(&self, locales: Self::LocalesIter, res_ids: Vec<ResourceId>) -> Self::Iter;
+
+    fn bundles_stream(&self, locales: Self::LocalesIter, res_ids: Vec<ResourceId>) -> Self::Stream;
 
-    fn bundles_iter(&self, _locales: Self::LocalesIter, _res_ids: Vec<ResourceId>) -> Self::Iter {
-        unimplemented!();
+    /// Reloads the resources which changed since they were loaded, returning
+    /// `true` if any did. Generators which don't cache resources return `false`.
+    fn reload(&self) -> bool {
+        false
     }
 
-    fn bundles_stream(
-        &self,
-        _locales: Self::LocalesIter,
-        _res_ids: Vec<ResourceId>,
-    ) -> Self::Stream {
-        unimplemented!();
+    /// Returns whether the resource `res_id` is available for `locale`.
+    ///
+    /// When optional resources are added to or removed from a
+    /// [`Localization`](crate::Localization), the bundles of the locales they
+    /// are missing in stay the same and are kept. Generators which can't tell
+    /// return `true`, so that the bundles are generated again.
+    fn has_resource(&self, _locale: &LanguageIdentifier, _res_id: &ResourceId) -> bool {
+        true
+    }
+}
+
+/// A [`Stream`] yielding the items of an [`Iterator`], which lets a
+/// synchronous [`BundleGenerator::Iter`] serve as its
+/// [`BundleGenerator::Stream`] too.
+pub struct IterStream<I> {
+    iter: I,
+}
+
+impl<I> IterStream<I> {
+    pub fn new(iter: I) -> Self {
+        Self { iter }
+    }
+}
+
+impl<I> From<I> for IterStream<I> {
+    fn from(iter: I) -> Self {
+        Self::new(iter)
+    }
+}
+
+impl<I> Stream for IterStream<I>
+where
+    I: Iterator + Unpin,
+{
+    type Item = I::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Poll::Ready(self.iter.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl<I> BundleStream for IterStream<I>
+where
+    I: BundleIterator,
+{
+    async fn prefetch_async(&mut self) {
+        self.iter.prefetch_sync()
     }
 }
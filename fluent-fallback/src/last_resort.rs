@@ -0,0 +1,148 @@
+use crate::{
+    bundles::format_message_from_bundle,
+    errors::LocalizationError,
+    types::{L10nKey, L10nMessage},
+};
+use fluent_bundle::{bundle, memoizer::MemoizerKind, FluentArgs, FluentBundle, FluentResource};
+use std::borrow::Cow;
+
+/// What to render for keys which couldn't be formatted in any locale.
+///
+/// The policy is set with [`Localization::set_last_resort`](crate::Localization::set_last_resort)
+/// and applies to the values and messages returned by [`Bundles`](crate::Bundles).
+/// The errors about the missing keys are reported regardless of the policy.
+///
+/// `B` is the type of the embedded bundle, which is a
+/// [`concurrent::FluentBundle`](fluent_bundle::concurrent::FluentBundle) for
+/// the [`concurrent::LastResort`](crate::concurrent::LastResort).
+///
+/// # Example
+///
+/// ```
+/// use fluent_fallback::{LastResort, Localization};
+/// use fluent_resmgr::ResourceManager;
+/// use std::borrow::Cow;
+/// use unic_langid::langid;
+///
+/// fn pseudo_id(id: &str) -> Cow<'_, str> {
+///     fluent_pseudo::transform(id, false, true)
+/// }
+///
+/// let res_mgr = ResourceManager::new("./tests/resources/{locale}/{res_id}".to_string());
+/// let mut loc = Localization::with_env(
+///     vec!["test.ftl".into()],
+///     true,
+///     vec![langid!("en-US")],
+///     res_mgr,
+/// );
+/// loc.set_last_resort(LastResort::Pseudo(pseudo_id));
+///
+/// let mut errors = vec![];
+/// let value = loc
+///     .bundles()
+///     .format_value_sync("missing-message", None, &mut errors)
+///     .expect("Failed to format a value.");
+/// assert_eq!(value.as_deref(), Some("ḿişşiƞɠ-ḿeeşşaaɠee"));
+/// assert_eq!(errors.len(), 2);
+/// ```
+#[derive(Default)]
+pub enum LastResort<B = FluentBundle<FluentResource>> {
+    /// Keys are left without a translation.
+    #[default]
+    None,
+    /// The id of the key is rendered.
+    Id,
+    /// The id of the key is rendered after being transformed, for example
+    /// pseudolocalized with `fluent-pseudo`.
+    Pseudo(fn(&str) -> Cow<'_, str>),
+    /// The function is called with the id and the arguments of the key.
+    Callback(fn(&str, Option<&FluentArgs>) -> String),
+    /// The key is formatted using a bundle, typically of the default locale,
+    /// embedded in the binary. Keys missing from the bundle are left
+    /// without a translation.
+    Bundle(B),
+}
+
+impl<M> LastResort<bundle::FluentBundle<FluentResource, M>>
+where
+    M: MemoizerKind,
+{
+    pub(crate) fn format_value<'l>(
+        &'l self,
+        id: &'l str,
+        args: Option<&'l FluentArgs>,
+        errors: &mut Vec<LocalizationError>,
+    ) -> Option<Cow<'l, str>> {
+        match self {
+            Self::None => None,
+            Self::Id => Some(id.into()),
+            Self::Pseudo(transform) => Some(transform(id)),
+            Self::Callback(callback) => Some(callback(id, args).into()),
+            Self::Bundle(bundle) => {
+                let value = bundle.get_message(id)?.value()?;
+                let mut format_errors = vec![];
+                let value = bundle.format_pattern(value, args, &mut format_errors);
+                if !format_errors.is_empty() {
+                    errors.push(LocalizationError::Resolver {
+                        id: id.to_string(),
+                        locale: bundle.locales[0].clone(),
+                        errors: format_errors,
+                    });
+                }
+                Some(value)
+            }
+        }
+    }
+
+    pub(crate) fn format_message<'l>(
+        &'l self,
+        key: &'l L10nKey<'l>,
+        errors: &mut Vec<LocalizationError>,
+    ) -> Option<L10nMessage<'l>> {
+        if let Self::Bundle(bundle) = self {
+            let mut format_errors = vec![];
+            let message = format_message_from_bundle(bundle, key, &mut format_errors);
+            if !format_errors.is_empty() {
+                errors.push(LocalizationError::Resolver {
+                    id: key.id.to_string(),
+                    locale: bundle.locales[0].clone(),
+                    errors: format_errors,
+                });
+            }
+            return message;
+        }
+        let value = self.format_value(&key.id, key.args.as_ref(), errors)?;
+        Some(L10nMessage {
+            value: Some(value),
+            attributes: vec![],
+        })
+    }
+
+    /// Formats the values which no locale could provide.
+    pub(crate) fn format_missing_values<'l, 'v>(
+        &'l self,
+        keys: &'l [L10nKey<'l>],
+        values: impl Iterator<Item = &'v mut Option<Cow<'l, str>>>,
+        errors: &mut Vec<LocalizationError>,
+    ) where
+        'l: 'v,
+    {
+        for (key, value) in keys.iter().zip(values).filter(|(_, v)| v.is_none()) {
+            *value = self.format_value(&key.id, key.args.as_ref(), errors);
+        }
+    }
+
+    /// Formats the messages which no locale could provide.
+    pub(crate) fn format_missing_messages<'l, 'v>(
+        &'l self,
+        keys: &'l [L10nKey<'l>],
+        messages: impl Iterator<Item = &'v mut Option<L10nMessage<'l>>>,
+        errors: &mut Vec<LocalizationError>,
+    ) where
+        'l: 'v,
+    {
+        for (key, message) in keys.iter().zip(messages).filter(|(_, m)| m.is_none()) {
+            *message = self.format_message(key, errors);
+        }
+    }
+}
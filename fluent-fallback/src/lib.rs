@@ -28,7 +28,7 @@
 //! use fluent_resmgr::ResourceManager;
 //! use unic_langid::langid;
 //!
-//! let res_mgr = ResourceManager::new("./tests/resources/{locale}/".to_string());
+//! let res_mgr = ResourceManager::new("./tests/resources/{locale}/{res_id}".to_string());
 //!
 //! let loc = Localization::with_env(
 //!     vec![
@@ -77,9 +77,8 @@
 //! [`generator::BundleGenerator`] trait which provides a method to generate an
 //! [`Iterator`] and [`Stream`](futures::stream::Stream).
 //!
-//! This is not very elegant and will likely be improved in the future, but for the time being, if
-//! the customer doesn't need one of the modes, the unnecessary method should use the
-//! `unimplemented!()` macro as its body.
+//! A generator which only loads bundles synchronously can wrap its iterator in
+//! [`generator::IterStream`] to provide the stream, so that it can be used in both modes.
 //!
 //! `fluent-resmgr` provides a simple resource manager which handles synchronous I/O
 //! and uses local file system to store resources in a directory structure.
@@ -104,15 +103,24 @@
 //! next translation request.
 //!
 //! See [`env::LocalesProvider`] trait for an example of a reactive system implementation.
+#[macro_use]
 mod bundles;
 mod cache;
+mod changes;
+pub mod concurrent;
 pub mod env;
 mod errors;
 pub mod generator;
+mod last_resort;
 mod localization;
-mod pin_cell;
+pub mod memoizer;
+mod segments;
+mod strict;
 pub mod types;
 
 pub use bundles::Bundles;
-pub use errors::LocalizationError;
+pub use changes::{LocalizationChange, SubscriptionId};
+pub use errors::{ErrorSink, LocalizationError};
+pub use last_resort::LastResort;
 pub use localization::Localization;
+pub use strict::StrictBundles;
@@ -1,110 +1,305 @@
+// `LocalizationError` is returned as is by the synchronous public methods.
+#![allow(clippy::result_large_err)]
+
 use crate::{
     bundles::Bundles,
+    changes::{LocalizationChange, Observers, SubscriptionId},
     env::LocalesProvider,
+    errors::{ErrorReporter, LocalizationError},
     generator::{BundleGenerator, BundleIterator, BundleStream},
+    last_resort::LastResort,
+    memoizer::{IntoBoxed, LocalizationMemoizer},
+    strict::StrictBundles,
     types::ResourceId,
 };
+use fluent_bundle::{bundle::FluentBundle, FluentResource};
+use intl_memoizer::IntlLangMemoizer;
 use once_cell::sync::OnceCell;
-use std::rc::Rc;
+use std::sync::Mutex;
+use unic_langid::LanguageIdentifier;
+
+/// The bundles of a [`Localization`], along with what they're shared with.
+type SharedBundles<G, M> = <M as LocalizationMemoizer>::Shared<Bundles<G, M>>;
 
-pub struct Localization<G, P>
+/// Generates the bundles of the locales of a [`LocalesProvider`] with a
+/// [`BundleGenerator`], and keeps them up to date as the locales, the
+/// resources and the settings change.
+///
+/// The bundles use the memoizer `M`, and the state of the localization is
+/// shared with [`LocalizationMemoizer::Shared`], see
+/// [`concurrent::Localization`](crate::concurrent::Localization) for the
+/// thread-safe one.
+pub struct Localization<G, P, M = IntlLangMemoizer>
 where
-    G: BundleGenerator<LocalesIter = P::Iter>,
+    G: BundleGenerator<M, LocalesIter = P::Iter>,
     P: LocalesProvider,
+    M: LocalizationMemoizer,
 {
-    bundles: OnceCell<Rc<Bundles<G>>>,
+    bundles: OnceCell<SharedBundles<G, M>>,
+    // The bundles which were invalidated without dropping what they
+    // cached, to take the bundles of the locales still present from.
+    previous: Mutex<Option<SharedBundles<G, M>>>,
     generator: G,
     provider: P,
     sync: bool,
     res_ids: Vec<ResourceId>,
+    last_resort: M::Shared<LastResort<FluentBundle<FluentResource, M>>>,
+    error_reporter: M::Shared<ErrorReporter<M::Sink>>,
+    attribute_fallback: bool,
+    // The locales of the provider when they were last checked for changes.
+    locales: Vec<LanguageIdentifier>,
+    observers: Observers<M::Observer>,
 }
 
-impl<G, P> Localization<G, P>
+impl<G, P, M> Localization<G, P, M>
 where
-    G: BundleGenerator<LocalesIter = P::Iter> + Default,
+    G: BundleGenerator<M, LocalesIter = P::Iter> + Default,
     P: LocalesProvider + Default,
+    M: LocalizationMemoizer,
 {
     pub fn new(res_ids: Vec<ResourceId>, sync: bool) -> Self {
-        Self {
-            bundles: OnceCell::new(),
-            generator: G::default(),
-            provider: P::default(),
-            sync,
-            res_ids,
-        }
+        Self::with_env(res_ids, sync, P::default(), G::default())
     }
 }
 
-impl<G, P> Localization<G, P>
+impl<G, P, M> Localization<G, P, M>
 where
-    G: BundleGenerator<LocalesIter = P::Iter>,
+    G: BundleGenerator<M, LocalesIter = P::Iter>,
     P: LocalesProvider,
+    M: LocalizationMemoizer,
 {
     pub fn with_env(res_ids: Vec<ResourceId>, sync: bool, provider: P, generator: G) -> Self {
         Self {
             bundles: OnceCell::new(),
+            previous: Mutex::new(None),
+            locales: provider.locales().collect(),
             generator,
             provider,
             sync,
             res_ids,
+            last_resort: M::share(Default::default()),
+            error_reporter: M::share(Default::default()),
+            attribute_fallback: false,
+            observers: Default::default(),
         }
     }
 
+    /// Returns the generator of bundles used by this localization.
+    pub fn generator(&self) -> &G {
+        &self.generator
+    }
+
+    /// Returns the provider of the locales used by this localization.
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Updates the provider of the locales with `update`, returning its
+    /// result. If the locales changed, the bundles are invalidated and the
+    /// observers notified. The bundles of the locales which are still
+    /// present are kept, see [`LocalesProvider::locales_subset`].
+    ///
+    /// This is how providers whose locales change, such as
+    /// [`NegotiatedLocales`](crate::env::NegotiatedLocales), are kept in
+    /// sync with the localization.
+    pub fn update_provider<F, T>(&mut self, update: F) -> T
+    where
+        F: FnOnce(&mut P) -> T,
+    {
+        let result = update(&mut self.provider);
+        self.update_locales();
+        result
+    }
+
     pub fn is_sync(&self) -> bool {
         self.sync
     }
 
+    /// Adds a resource, invalidating the bundles unless it's already
+    /// present with the same [`ResourceType`](crate::types::ResourceType).
+    ///
+    /// Since the bundle of every locale is generated from all the
+    /// resources, they're generated again, except those of the locales an
+    /// optional resource is missing in, see [`BundleGenerator::has_resource`].
     pub fn add_resource_id<T: Into<ResourceId>>(&mut self, res_id: T) {
-        self.res_ids.push(res_id.into());
-        self.on_change();
+        self.add_resource_ids(vec![res_id.into()]);
     }
 
     pub fn add_resource_ids(&mut self, res_ids: Vec<ResourceId>) {
-        self.res_ids.extend(res_ids);
-        self.on_change();
+        let added: Vec<_> = res_ids
+            .into_iter()
+            .filter(|res_id| insert_resource_id(&mut self.res_ids, res_id.clone()))
+            .collect();
+        if !added.is_empty() {
+            self.renew();
+            self.observers
+                .notify(&LocalizationChange::ResourcesAdded(added));
+        }
     }
 
+    /// Removes a resource, invalidating the bundles only if it was present.
+    /// Like in [`add_resource_id`](Self::add_resource_id), the bundles of the
+    /// locales an optional resource is missing in are kept.
     pub fn remove_resource_id<T: PartialEq<ResourceId>>(&mut self, res_id: T) -> usize {
-        self.res_ids.retain(|x| !res_id.eq(x));
-        self.on_change();
-        self.res_ids.len()
+        self.remove_resource_ids_by(|x| res_id.eq(x))
     }
 
     pub fn remove_resource_ids(&mut self, res_ids: Vec<ResourceId>) -> usize {
-        self.res_ids.retain(|x| !res_ids.contains(x));
-        self.on_change();
+        self.remove_resource_ids_by(|x| res_ids.contains(x))
+    }
+
+    fn remove_resource_ids_by<F: Fn(&ResourceId) -> bool>(&mut self, predicate: F) -> usize {
+        let (removed, kept) = self.res_ids.drain(..).partition(predicate);
+        self.res_ids = kept;
+        if !removed.is_empty() {
+            self.renew();
+            self.observers
+                .notify(&LocalizationChange::ResourcesRemoved(removed));
+        }
         self.res_ids.len()
     }
 
     pub fn set_async(&mut self) {
         if self.sync {
             self.sync = false;
-            self.on_change();
+            self.invalidate();
+        }
+    }
+
+    /// Sets what to render for keys which can't be formatted in any locale.
+    pub fn set_last_resort(&mut self, last_resort: LastResort<FluentBundle<FluentResource, M>>) {
+        self.last_resort = M::share(last_resort);
+        self.renew();
+    }
+
+    pub fn last_resort(&self) -> &LastResort<FluentBundle<FluentResource, M>> {
+        &self.last_resort
+    }
+
+    /// Sets the sink which receives each distinct formatting error once.
+    ///
+    /// Sinks are [`ErrorSink`](crate::ErrorSink)s, which are also [`Send`]
+    /// and [`Sync`] for the [`concurrent::Localization`](crate::concurrent::Localization).
+    pub fn set_error_sink<S: IntoBoxed<M::Sink>>(&mut self, sink: S) {
+        self.error_reporter = M::share(ErrorReporter::new(sink.into_boxed()));
+        self.renew();
+    }
+
+    /// Sets whether formatted messages take the attributes missing from
+    /// their translation from the first following locale which has the
+    /// message.
+    ///
+    /// This makes `format_messages` resolve the bundles of the following
+    /// locales, until each message with a translation was found in one.
+    pub fn set_attribute_fallback(&mut self, attribute_fallback: bool) {
+        if self.attribute_fallback != attribute_fallback {
+            self.attribute_fallback = attribute_fallback;
+            self.renew();
         }
     }
 
+    pub fn has_attribute_fallback(&self) -> bool {
+        self.attribute_fallback
+    }
+
+    /// Subscribes `observer` to the changes of this localization, returning
+    /// the id to [`unsubscribe`](Self::unsubscribe) it with.
+    ///
+    /// Observers are also [`Send`] and [`Sync`] for the
+    /// [`concurrent::Localization`](crate::concurrent::Localization).
+    pub fn subscribe<F>(&mut self, observer: F) -> SubscriptionId
+    where
+        F: IntoBoxed<M::Observer>,
+    {
+        self.observers.subscribe(observer.into_boxed())
+    }
+
+    /// Unsubscribes an observer, returning whether it was subscribed.
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        self.observers.unsubscribe(id)
+    }
+
+    /// Reloads the resources of the generator, see
+    /// [`BundleGenerator::reload`]. The bundles are invalidated and the
+    /// observers notified if any resource changed.
+    pub fn reload(&mut self) -> bool {
+        let reloaded = self.generator.reload();
+        if reloaded {
+            self.invalidate();
+            self.observers
+                .notify(&LocalizationChange::ResourcesReloaded);
+        }
+        reloaded
+    }
+
+    /// Drops the bundles, so they're generated again on the next use.
+    ///
+    /// This should be called when the locales of the provider or the
+    /// resources of the generator change. If the locales changed, the
+    /// bundles of the locales which are still present are kept, and the
+    /// observers notified. Otherwise the resources are assumed to have
+    /// changed, and all the bundles are rebuilt from the generator, so
+    /// generators which cache their parsed resources, like the ones of
+    /// `fluent-resmgr`, only load the resources which have changed.
+    ///
+    /// When both change at once, the resources should be
+    /// [`reload`](Self::reload)ed first. Changes made through
+    /// [`update_provider`](Self::update_provider) are applied without this
+    /// call.
     pub fn on_change(&mut self) {
+        if !self.update_locales() {
+            self.invalidate();
+        }
+    }
+
+    fn invalidate(&mut self) {
         self.bundles.take();
+        self.previous.get_mut().unwrap().take();
+    }
+
+    /// Drops the bundles, keeping what they cached for the next ones, which
+    /// take the bundles which are still the same from them.
+    fn renew(&mut self) {
+        if let Some(bundles) = self.bundles.take() {
+            *self.previous.get_mut().unwrap() = Some(bundles);
+        }
+    }
+
+    /// Invalidates the bundles and notifies the observers if the locales
+    /// of the provider changed since they were last checked, returning
+    /// whether they did.
+    fn update_locales(&mut self) -> bool {
+        let locales: Vec<_> = self.provider.locales().collect();
+        let changed = locales != self.locales;
+        if changed {
+            self.locales = locales;
+            self.renew();
+            self.observers.notify(&LocalizationChange::Locales);
+        }
+        changed
     }
 }
 
-impl<G, P> Localization<G, P>
+impl<G, P, M> Localization<G, P, M>
 where
-    G: BundleGenerator<LocalesIter = P::Iter>,
+    G: BundleGenerator<M, LocalesIter = P::Iter>,
     G::Iter: BundleIterator,
     P: LocalesProvider,
+    M: LocalizationMemoizer,
 {
-    pub fn prefetch_sync(&mut self) {
+    pub fn prefetch_sync(&mut self) -> Result<(), LocalizationError> {
         let bundles = self.bundles();
-        bundles.prefetch_sync();
+        bundles.prefetch_sync()
     }
 }
 
-impl<G, P> Localization<G, P>
+impl<G, P, M> Localization<G, P, M>
 where
-    G: BundleGenerator<LocalesIter = P::Iter>,
+    G: BundleGenerator<M, LocalesIter = P::Iter>,
+    G::Iter: BundleIterator,
     G::Stream: BundleStream,
     P: LocalesProvider,
+    M: LocalizationMemoizer,
 {
     pub async fn prefetch_async(&mut self) {
         let bundles = self.bundles();
@@ -112,19 +307,63 @@ where
     }
 }
 
-impl<G, P> Localization<G, P>
+impl<G, P, M> Localization<G, P, M>
 where
-    G: BundleGenerator<LocalesIter = P::Iter>,
+    G: BundleGenerator<M, LocalesIter = P::Iter>,
     P: LocalesProvider,
+    M: LocalizationMemoizer,
 {
-    pub fn bundles(&self) -> &Rc<Bundles<G>> {
+    /// Returns the bundles, generating them on first use.
+    ///
+    /// The returned pointer can be cloned to keep using the bundles after
+    /// the localization changes. They keep the previous locales and
+    /// resources then.
+    pub fn bundles(&self) -> &SharedBundles<G, M> {
         self.bundles.get_or_init(|| {
-            Rc::new(Bundles::new(
-                self.sync,
-                self.res_ids.clone(),
-                &self.generator,
-                &self.provider,
-            ))
+            let previous = self.previous.lock().unwrap().take();
+            let bundles = match previous {
+                Some(previous) => Bundles::reusing(
+                    &previous,
+                    self.sync,
+                    self.res_ids.clone(),
+                    &self.generator,
+                    &self.provider,
+                ),
+                None => Bundles::new(
+                    self.sync,
+                    self.res_ids.clone(),
+                    &self.generator,
+                    &self.provider,
+                ),
+            };
+            M::share(
+                bundles
+                    .with_last_resort(self.last_resort.clone())
+                    .with_error_reporter(self.error_reporter.clone())
+                    .with_attribute_fallback(self.attribute_fallback),
+            )
         })
     }
+
+    /// Returns a view of the bundles whose formatting methods fail on any
+    /// fallback, see [`StrictBundles`].
+    pub fn strict(&self) -> StrictBundles<'_, G, M> {
+        self.bundles().strict()
+    }
+}
+
+/// Adds `res_id` to `res_ids`, or updates the type of the matching resource.
+/// Returns `false` if `res_ids` already had it.
+pub(crate) fn insert_resource_id(res_ids: &mut Vec<ResourceId>, res_id: ResourceId) -> bool {
+    match res_ids.iter_mut().find(|x| **x == res_id) {
+        Some(x) if x.resource_type == res_id.resource_type => false,
+        Some(x) => {
+            x.resource_type = res_id.resource_type;
+            true
+        }
+        None => {
+            res_ids.push(res_id);
+            true
+        }
+    }
 }
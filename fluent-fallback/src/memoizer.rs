@@ -0,0 +1,82 @@
+//! The memoizers which the bundles of a [`Localization`](crate::Localization)
+//! can use, and the types the localization shares its state with for each of
+//! them.
+//!
+//! The bundles of a [`Localization`](crate::Localization) use the
+//! [`IntlLangMemoizer`], and the state is shared with [`Rc`]. The ones of a
+//! [`concurrent::Localization`](crate::concurrent::Localization) use the
+//! concurrent [`IntlLangMemoizer`](intl_memoizer::concurrent::IntlLangMemoizer),
+//! and the state is shared with [`Arc`], so that the localization is [`Send`]
+//! and [`Sync`] whenever its generator and provider are.
+use crate::{changes::LocalizationChange, errors::ErrorSink};
+use fluent_bundle::memoizer::MemoizerKind;
+use intl_memoizer::{concurrent, IntlLangMemoizer};
+use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// A memoizer of the bundles of a [`Localization`](crate::Localization),
+/// along with the types used to share the localization state.
+pub trait LocalizationMemoizer: MemoizerKind {
+    /// The pointer the bundles and their settings are shared with.
+    type Shared<T>: Deref<Target = T> + Clone;
+    /// The [`ErrorSink`] trait object which errors are reported to.
+    type Sink: ErrorSink + ?Sized;
+    /// The trait object which observers are boxed into.
+    type Observer: Fn(&LocalizationChange) + ?Sized;
+
+    fn share<T>(value: T) -> Self::Shared<T>;
+}
+
+impl LocalizationMemoizer for IntlLangMemoizer {
+    type Shared<T> = Rc<T>;
+    type Sink = dyn ErrorSink;
+    type Observer = dyn Fn(&LocalizationChange);
+
+    fn share<T>(value: T) -> Rc<T> {
+        Rc::new(value)
+    }
+}
+
+impl LocalizationMemoizer for concurrent::IntlLangMemoizer {
+    type Shared<T> = Arc<T>;
+    type Sink = dyn ErrorSink + Send + Sync;
+    type Observer = dyn Fn(&LocalizationChange) + Send + Sync;
+
+    fn share<T>(value: T) -> Arc<T> {
+        Arc::new(value)
+    }
+}
+
+/// Boxes a value into the trait object `T`, such as the
+/// [`LocalizationMemoizer::Sink`] of error sinks.
+pub trait IntoBoxed<T: ?Sized> {
+    fn into_boxed(self) -> Box<T>;
+}
+
+impl<S: ErrorSink + 'static> IntoBoxed<dyn ErrorSink> for S {
+    fn into_boxed(self) -> Box<dyn ErrorSink> {
+        Box::new(self)
+    }
+}
+
+impl<S: ErrorSink + Send + Sync + 'static> IntoBoxed<dyn ErrorSink + Send + Sync> for S {
+    fn into_boxed(self) -> Box<dyn ErrorSink + Send + Sync> {
+        Box::new(self)
+    }
+}
+
+impl<F: Fn(&LocalizationChange) + 'static> IntoBoxed<dyn Fn(&LocalizationChange)> for F {
+    fn into_boxed(self) -> Box<dyn Fn(&LocalizationChange)> {
+        Box::new(self)
+    }
+}
+
+impl<F> IntoBoxed<dyn Fn(&LocalizationChange) + Send + Sync> for F
+where
+    F: Fn(&LocalizationChange) + Send + Sync + 'static,
+{
+    fn into_boxed(self) -> Box<dyn Fn(&LocalizationChange) + Send + Sync> {
+        Box::new(self)
+    }
+}
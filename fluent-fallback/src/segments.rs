@@ -0,0 +1,218 @@
+//! The bundles of a [`Localization`](crate::Localization) are generated in
+//! batches, each by one call to the generator for a list of locales and
+//! resources. When the locales or the resources change, the bundles which
+//! are still the same are taken from the previous batches, and only the
+//! other ones are generated again.
+use crate::types::ResourceId;
+use fluent_bundle::bundle;
+use std::ops::{Deref, Range};
+use unic_langid::LanguageIdentifier;
+
+/// The cache of the bundles generated by one call to the generator.
+pub(crate) struct Batch<C> {
+    pub(crate) locales: Vec<LanguageIdentifier>,
+    pub(crate) res_ids: Vec<ResourceId>,
+    pub(crate) cache: C,
+}
+
+/// The bundles of the locales in `range` of a batch, yielded in place of
+/// consecutive locales of the bundles.
+pub(crate) struct Segment<B> {
+    pub(crate) batch: B,
+    pub(crate) range: Range<usize>,
+}
+
+/// What to do with a bundle of a batch when walking one of its segments.
+pub(crate) enum Step {
+    Skip,
+    Take,
+    End,
+}
+
+/// The walk through the bundles of a segment, with the cursor of the walk
+/// in the cache of the batch, and the position of the last bundle.
+pub(crate) struct Walk<'a, B, T> {
+    pub(crate) segment: &'a Segment<B>,
+    pub(crate) cursor: T,
+    pos: usize,
+}
+
+impl<'a, B, C, T> Walk<'a, B, T>
+where
+    B: Deref<Target = Batch<C>>,
+{
+    pub(crate) fn new(segment: &'a Segment<B>, cursor: T) -> Self {
+        Self {
+            segment,
+            cursor,
+            pos: 0,
+        }
+    }
+
+    /// Returns what to do with the next bundle of the batch, for `locale`.
+    pub(crate) fn step(&mut self, locale: Option<&LanguageIdentifier>) -> Step {
+        self.segment
+            .batch
+            .step(&self.segment.range, locale, &mut self.pos)
+    }
+}
+
+/// A cache of generated bundles.
+pub(crate) trait BundleCache {
+    /// Returns the locales of the bundles generated so far, without
+    /// generating more of them.
+    fn generated_locales(&self) -> Vec<&LanguageIdentifier>;
+}
+
+/// A bundle yielded by a generator.
+pub(crate) trait GeneratedBundle {
+    /// Returns the locale of the bundle.
+    fn locale(&self) -> Option<&LanguageIdentifier>;
+}
+
+impl<R, E, M> GeneratedBundle
+    for Result<bundle::FluentBundle<R, M>, (bundle::FluentBundle<R, M>, Vec<E>)>
+{
+    fn locale(&self) -> Option<&LanguageIdentifier> {
+        let bundle = self.as_ref().unwrap_or_else(|(bundle, _)| bundle);
+        bundle.locales.first()
+    }
+}
+
+impl<C> Batch<C> {
+    pub(crate) fn new(
+        locales: Vec<LanguageIdentifier>,
+        res_ids: Vec<ResourceId>,
+        cache: C,
+    ) -> Self {
+        Self {
+            locales,
+            res_ids,
+            cache,
+        }
+    }
+
+    /// Returns the position in the locales of the batch of a bundle for
+    /// `locale`, following one at `pos`. Bundles for locales which the batch
+    /// wasn't generated for, and generators yielding them out of order, keep
+    /// the position of the previous bundle.
+    fn position(&self, locale: Option<&LanguageIdentifier>, pos: usize) -> usize {
+        self.locales
+            .iter()
+            .position(|l| Some(l) == locale)
+            .map_or(pos, |found| found.max(pos))
+    }
+
+    /// Returns what to do with the next bundle, for `locale`, when walking
+    /// the locales in `range`, updating `pos` to its position.
+    pub(crate) fn step(
+        &self,
+        range: &Range<usize>,
+        locale: Option<&LanguageIdentifier>,
+        pos: &mut usize,
+    ) -> Step {
+        *pos = self.position(locale, *pos);
+        if *pos < range.start {
+            Step::Skip
+        } else if *pos < range.end {
+            Step::Take
+        } else {
+            Step::End
+        }
+    }
+}
+
+impl<C: BundleCache> Batch<C> {
+    /// Returns the number of locales of the batch which the generator
+    /// reached, the last one possibly with more bundles to come.
+    fn reached(&self) -> usize {
+        let mut pos = 0;
+        let mut reached = 0;
+        for locale in self.cache.generated_locales() {
+            pos = self.position(Some(locale), pos);
+            reached = pos + 1;
+        }
+        reached
+    }
+}
+
+/// The locales of the new bundles served by the previous bundles, or the
+/// range of the new locales to generate again.
+pub(crate) enum Plan<B> {
+    Reuse(Segment<B>),
+    Generate(Range<usize>),
+}
+
+/// Returns whether the bundle of a locale generated from the `previous`
+/// resources is the one the `current` resources generate, that is whether
+/// every resource which is in only one of them, or with another type, is
+/// optional and missing in the locale according to `has_resource`.
+pub(crate) fn same_bundle<F>(
+    previous: &[ResourceId],
+    current: &[ResourceId],
+    has_resource: F,
+) -> bool
+where
+    F: Fn(&ResourceId) -> bool,
+{
+    let contains = |res_ids: &[ResourceId], res_id: &ResourceId| {
+        res_ids
+            .iter()
+            .any(|x| x == res_id && x.resource_type == res_id.resource_type)
+    };
+    previous
+        .iter()
+        .filter(|res_id| !contains(current, res_id))
+        .chain(current.iter().filter(|res_id| !contains(previous, res_id)))
+        .all(|res_id| res_id.is_optional() && !has_resource(res_id))
+}
+
+/// Plans the bundles of `locales` from the `previous` ones, merging the
+/// consecutive locales of the same batch into one segment, and the
+/// consecutive locales to generate into one range.
+///
+/// A locale is taken from its previous batch if `reusable` returns `true`
+/// for the batch, unless walking the batch to it would generate the bundles
+/// of locales which were removed.
+pub(crate) fn plan<B, C, F>(
+    previous: &[Segment<B>],
+    locales: &[LanguageIdentifier],
+    reusable: F,
+) -> Vec<Plan<B>>
+where
+    B: Deref<Target = Batch<C>> + Clone,
+    C: BundleCache,
+    F: Fn(&Batch<C>, &LanguageIdentifier) -> bool,
+{
+    let mut plans: Vec<Plan<B>> = vec![];
+    for (pos, locale) in locales.iter().enumerate() {
+        let found = previous.iter().find_map(|segment| {
+            segment
+                .range
+                .clone()
+                .find(|&idx| segment.batch.locales[idx] == *locale)
+                .filter(|_| reusable(&segment.batch, locale))
+                .map(|idx| (segment, idx))
+        });
+        let reusable = found.filter(|(segment, idx)| {
+            let reached = segment.batch.reached().min(*idx);
+            segment.batch.locales[reached..*idx]
+                .iter()
+                .all(|l| locales.contains(l))
+        });
+        match (plans.last_mut(), reusable) {
+            (Some(Plan::Reuse(last)), Some((segment, idx)))
+                if std::ptr::eq(&*last.batch, &*segment.batch) && last.range.end == idx =>
+            {
+                last.range.end += 1;
+            }
+            (_, Some((segment, idx))) => plans.push(Plan::Reuse(Segment {
+                batch: segment.batch.clone(),
+                range: idx..idx + 1,
+            })),
+            (Some(Plan::Generate(range)), None) => range.end += 1,
+            (_, None) => plans.push(Plan::Generate(pos..pos + 1)),
+        }
+    }
+    plans
+}
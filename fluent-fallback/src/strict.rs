@@ -0,0 +1,188 @@
+use crate::{
+    bundles::Bundles,
+    errors::LocalizationError,
+    generator::BundleGenerator,
+    memoizer::LocalizationMemoizer,
+    types::{L10nKey, L10nMessage},
+};
+use fluent_bundle::{FluentArgs, FluentError};
+use intl_memoizer::IntlLangMemoizer;
+use std::borrow::Cow;
+
+/// A view of [`Bundles`] whose formatting methods fail if any key needs
+/// fallback, returning the errors which caused it.
+///
+/// This includes keys which fall back to a later locale or to the
+/// [`LastResort`](crate::LastResort), resolver errors, and resources of the
+/// primary locale which fail to parse. Resources which fail to load, such as
+/// missing optional ones, don't fail it. It's intended for tests verifying
+/// that a locale is complete.
+///
+/// # Example
+///
+/// ```
+/// use fluent_fallback::Localization;
+/// use fluent_resmgr::ResourceManager;
+/// use unic_langid::langid;
+///
+/// let res_mgr = ResourceManager::new("./tests/resources/{locale}/{res_id}".to_string());
+/// let loc = Localization::with_env(
+///     vec!["test.ftl".into()],
+///     true,
+///     vec![langid!("pl"), langid!("en-US")],
+///     res_mgr,
+/// );
+/// let bundles = loc.strict();
+///
+/// let value = bundles.format_value_sync("hello-world", None);
+/// assert_eq!(value.unwrap().as_deref(), Some("Hello World [pl]"));
+///
+/// let errors = bundles.format_value_sync("missing-message", None).unwrap_err();
+/// assert_eq!(errors.len(), 3);
+/// ```
+pub struct StrictBundles<'b, G, M = IntlLangMemoizer>
+where
+    G: BundleGenerator<M>,
+    M: LocalizationMemoizer,
+{
+    bundles: &'b Bundles<G, M>,
+}
+
+impl<G, M> Bundles<G, M>
+where
+    G: BundleGenerator<M>,
+    M: LocalizationMemoizer,
+{
+    /// Returns a view of the bundles whose formatting methods fail on any
+    /// fallback, see [`StrictBundles`].
+    pub fn strict(&self) -> StrictBundles<'_, G, M> {
+        StrictBundles { bundles: self }
+    }
+}
+
+/// Keeps the errors of the lookup which need fallback, along with the parse
+/// errors of the primary locale in place of the other errors of bundles.
+pub(crate) fn into_result<T>(
+    value: T,
+    errors: Vec<LocalizationError>,
+    primary_errors: Vec<LocalizationError>,
+) -> Result<T, Vec<LocalizationError>> {
+    let errors: Vec<_> = primary_errors
+        .into_iter()
+        .filter(|error| {
+            matches!(
+                error,
+                LocalizationError::Bundle {
+                    error: FluentError::ParserError(_),
+                    ..
+                }
+            )
+        })
+        .chain(errors.into_iter().filter(|error| {
+            !matches!(
+                error,
+                LocalizationError::Bundle { .. } | LocalizationError::ResourceLoad { .. }
+            )
+        }))
+        .collect();
+    if errors.is_empty() {
+        Ok(value)
+    } else {
+        Err(errors)
+    }
+}
+
+impl<'b, G, M> StrictBundles<'b, G, M>
+where
+    G: BundleGenerator<M>,
+    M: LocalizationMemoizer,
+{
+    pub async fn format_value<'l>(
+        &self,
+        id: &'l str,
+        args: Option<&'l FluentArgs<'_>>,
+    ) -> Result<Option<Cow<'l, str>>, Vec<LocalizationError>>
+    where
+        'b: 'l,
+    {
+        let mut errors = vec![];
+        let value = self.bundles.format_value(id, args, &mut errors).await;
+        into_result(value, errors, self.bundles.primary_errors().await)
+    }
+
+    pub async fn format_values<'l>(
+        &self,
+        keys: &'l [L10nKey<'l>],
+    ) -> Result<Vec<Option<Cow<'l, str>>>, Vec<LocalizationError>>
+    where
+        'b: 'l,
+    {
+        let mut errors = vec![];
+        let values = self.bundles.format_values(keys, &mut errors).await;
+        into_result(values, errors, self.bundles.primary_errors().await)
+    }
+
+    pub async fn format_messages<'l>(
+        &self,
+        keys: &'l [L10nKey<'l>],
+    ) -> Result<Vec<Option<L10nMessage<'l>>>, Vec<LocalizationError>>
+    where
+        'b: 'l,
+    {
+        let mut errors = vec![];
+        let messages = self.bundles.format_messages(keys, &mut errors).await;
+        into_result(messages, errors, self.bundles.primary_errors().await)
+    }
+
+    pub fn format_value_sync<'l>(
+        &self,
+        id: &'l str,
+        args: Option<&'l FluentArgs>,
+    ) -> Result<Option<Cow<'l, str>>, Vec<LocalizationError>>
+    where
+        'b: 'l,
+    {
+        let mut errors = vec![];
+        let value = self
+            .bundles
+            .format_value_sync(id, args, &mut errors)
+            .map_err(|error| vec![error])?;
+        into_result(value, errors, self.primary_errors_sync()?)
+    }
+
+    pub fn format_values_sync<'l>(
+        &self,
+        keys: &'l [L10nKey<'l>],
+    ) -> Result<Vec<Option<Cow<'l, str>>>, Vec<LocalizationError>>
+    where
+        'b: 'l,
+    {
+        let mut errors = vec![];
+        let values = self
+            .bundles
+            .format_values_sync(keys, &mut errors)
+            .map_err(|error| vec![error])?;
+        into_result(values, errors, self.primary_errors_sync()?)
+    }
+
+    pub fn format_messages_sync<'l>(
+        &self,
+        keys: &'l [L10nKey<'l>],
+    ) -> Result<Vec<Option<L10nMessage<'l>>>, Vec<LocalizationError>>
+    where
+        'b: 'l,
+    {
+        let mut errors = vec![];
+        let messages = self
+            .bundles
+            .format_messages_sync(keys, &mut errors)
+            .map_err(|error| vec![error])?;
+        into_result(messages, errors, self.primary_errors_sync()?)
+    }
+
+    fn primary_errors_sync(&self) -> Result<Vec<LocalizationError>, Vec<LocalizationError>> {
+        self.bundles
+            .primary_errors_sync()
+            .map_err(|error| vec![error])
+    }
+}
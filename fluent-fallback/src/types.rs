@@ -1,10 +1,16 @@
 use fluent_bundle::FluentArgs;
 use std::borrow::Cow;
+use unic_langid::LanguageIdentifier;
 
 #[derive(Debug)]
 pub struct L10nKey<'l> {
     pub id: Cow<'l, str>,
     pub args: Option<FluentArgs<'l>>,
+    /// The attributes to format, or `None` to format all of them.
+    ///
+    /// When formatting messages, the requested attributes which the message
+    /// lacks are taken from the following locales.
+    pub attributes: Option<Vec<Cow<'l, str>>>,
 }
 
 impl<'l> From<&'l str> for L10nKey<'l> {
@@ -12,6 +18,7 @@ impl<'l> From<&'l str> for L10nKey<'l> {
         Self {
             id: id.into(),
             args: None,
+            attributes: None,
         }
     }
 }
@@ -28,6 +35,59 @@ pub struct L10nMessage<'l> {
     pub attributes: Vec<L10nAttribute<'l>>,
 }
 
+/// The reason why a locale couldn't provide the translation of a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FallbackReason {
+    /// The bundle of the locale doesn't have the message.
+    MissingMessage,
+    /// The message exists in the bundle of the locale, but it has no value.
+    MissingValue,
+    /// The message exists in the bundle of the locale, but it lacks some of
+    /// the requested attributes, which are taken from the following locales.
+    MissingAttribute,
+}
+
+/// A locale which was tried, but couldn't provide the translation of a key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FallbackAttempt {
+    pub locale: LanguageIdentifier,
+    pub reason: FallbackReason,
+}
+
+/// The translation of a key, along with the locale which provided it and
+/// the locales which were tried before it.
+///
+/// `T` is the formatted value, or the formatted [`L10nMessage`].
+#[derive(Debug, Clone)]
+pub struct FallbackReport<T> {
+    /// The translation, or `None` if no locale could provide it and the
+    /// [`LastResort`](crate::LastResort) doesn't render it either.
+    pub value: Option<T>,
+    /// The locale which provided the translation, or `None` if it comes
+    /// from the [`LastResort`](crate::LastResort).
+    pub locale: Option<LanguageIdentifier>,
+    /// The locales which were tried and failed, in fallback order.
+    pub attempts: Vec<FallbackAttempt>,
+}
+
+impl<T> FallbackReport<T> {
+    /// Returns `true` if the translation comes from a locale other than the
+    /// first one tried, or from the [`LastResort`](crate::LastResort).
+    pub fn is_fallback(&self) -> bool {
+        self.value.is_some() && (self.locale.is_none() || !self.attempts.is_empty())
+    }
+}
+
+impl<T> Default for FallbackReport<T> {
+    fn default() -> Self {
+        Self {
+            value: None,
+            locale: None,
+            attempts: vec![],
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ResourceType {
     /// This is a required resource.
@@ -0,0 +1,331 @@
+use std::borrow::Cow;
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentError, FluentResource};
+use fluent_fallback::{
+    concurrent::{FluentBundleResult, IntlLangMemoizer, LastResort, Localization},
+    generator::{BundleGenerator, BundleIterator, IterStream},
+    types::{L10nKey, ResourceId},
+    LocalizationChange, LocalizationError,
+};
+use futures::stream::{self, Iter};
+use unic_langid::{langid, LanguageIdentifier};
+
+struct ResourceManager;
+
+impl ResourceManager {
+    fn get_bundle(
+        locale: LanguageIdentifier,
+        res_ids: &[ResourceId],
+    ) -> FluentBundle<Arc<FluentResource>> {
+        let mut bundle = FluentBundle::new_concurrent(vec![locale.clone()]);
+        bundle.set_use_isolating(false);
+        for res_id in res_ids {
+            let full_path = format!("./tests/resources/{}/{}", locale, res_id.value);
+            let source = fs::read_to_string(full_path).unwrap();
+            let res = FluentResource::try_new(source).unwrap();
+            bundle.add_resource(Arc::new(res)).unwrap();
+        }
+        bundle
+    }
+}
+
+type BundleIter = std::vec::IntoIter<FluentBundleResult<Arc<FluentResource>>>;
+
+impl BundleGenerator<IntlLangMemoizer> for ResourceManager {
+    type Resource = Arc<FluentResource>;
+    type Error = FluentError;
+    type LocalesIter = std::vec::IntoIter<LanguageIdentifier>;
+    type Iter = BundleIter;
+    type Stream = Iter<BundleIter>;
+
+    fn bundles_iter(&self, locales: Self::LocalesIter, res_ids: Vec<ResourceId>) -> Self::Iter {
+        locales
+            .map(|locale| Self::get_bundle(locale, &res_ids))
+            .map(Ok)
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn bundles_stream(&self, locales: Self::LocalesIter, res_ids: Vec<ResourceId>) -> Self::Stream {
+        stream::iter(self.bundles_iter(locales, res_ids))
+    }
+}
+
+/// A generator recording the locales of the bundles it was asked for.
+#[derive(Default)]
+struct RecordingResourceManager {
+    calls: Mutex<Vec<Vec<LanguageIdentifier>>>,
+}
+
+impl BundleGenerator<IntlLangMemoizer> for RecordingResourceManager {
+    type Resource = Arc<FluentResource>;
+    type Error = FluentError;
+    type LocalesIter = std::vec::IntoIter<LanguageIdentifier>;
+    type Iter = BundleIter;
+    type Stream = Iter<BundleIter>;
+
+    fn bundles_iter(&self, locales: Self::LocalesIter, res_ids: Vec<ResourceId>) -> Self::Iter {
+        let locales: Vec<_> = locales.collect();
+        self.calls.lock().unwrap().push(locales.clone());
+        ResourceManager.bundles_iter(locales.into_iter(), res_ids)
+    }
+
+    fn bundles_stream(&self, locales: Self::LocalesIter, res_ids: Vec<ResourceId>) -> Self::Stream {
+        stream::iter(self.bundles_iter(locales, res_ids))
+    }
+}
+
+/// An iterator recording whether it was prefetched.
+struct PrefetchingIter {
+    bundles: BundleIter,
+    prefetched: Arc<AtomicBool>,
+}
+
+impl Iterator for PrefetchingIter {
+    type Item = FluentBundleResult<Arc<FluentResource>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.bundles.next()
+    }
+}
+
+impl BundleIterator for PrefetchingIter {
+    fn prefetch_sync(&mut self) {
+        self.prefetched.store(true, Ordering::SeqCst);
+    }
+}
+
+#[derive(Default)]
+struct PrefetchingResourceManager {
+    prefetched: Arc<AtomicBool>,
+}
+
+impl BundleGenerator<IntlLangMemoizer> for PrefetchingResourceManager {
+    type Resource = Arc<FluentResource>;
+    type Error = FluentError;
+    type LocalesIter = std::vec::IntoIter<LanguageIdentifier>;
+    type Iter = PrefetchingIter;
+    type Stream = IterStream<PrefetchingIter>;
+
+    fn bundles_iter(&self, locales: Self::LocalesIter, res_ids: Vec<ResourceId>) -> Self::Iter {
+        PrefetchingIter {
+            bundles: ResourceManager.bundles_iter(locales, res_ids),
+            prefetched: Arc::clone(&self.prefetched),
+        }
+    }
+
+    fn bundles_stream(&self, locales: Self::LocalesIter, res_ids: Vec<ResourceId>) -> Self::Stream {
+        self.bundles_iter(locales, res_ids).into()
+    }
+}
+
+fn get_localization(sync: bool) -> Localization<ResourceManager, Vec<LanguageIdentifier>> {
+    Localization::with_env(
+        vec!["test.ftl".into(), "test2.ftl".into()],
+        sync,
+        vec![langid!("pl"), langid!("en-US")],
+        ResourceManager,
+    )
+}
+
+#[test]
+fn concurrent_localization_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Localization<ResourceManager, Vec<LanguageIdentifier>>>();
+}
+
+#[test]
+fn concurrent_localization_settings() {
+    let mut loc = get_localization(true);
+    let changes = Arc::new(Mutex::new(vec![]));
+    let observer = Arc::clone(&changes);
+    loc.subscribe(move |change: &LocalizationChange| observer.lock().unwrap().push(change.clone()));
+    let reported = Arc::new(Mutex::new(vec![]));
+    let sink = Arc::clone(&reported);
+    loc.set_error_sink(move |error: &LocalizationError| {
+        sink.lock().unwrap().push(error.to_string())
+    });
+    loc.set_last_resort(LastResort::Id);
+    loc.set_attribute_fallback(true);
+
+    loc.remove_resource_id(ResourceId::from("test2.ftl"));
+    loc.add_resource_id("test2.ftl");
+    assert_eq!(changes.lock().unwrap().len(), 2);
+
+    let loc = Arc::new(loc);
+    let handle = thread::spawn({
+        let loc = Arc::clone(&loc);
+        move || {
+            let keys = vec![L10nKey::from("message-5"), L10nKey::from("missing-message")];
+            let mut errors = vec![];
+            let reports = loc
+                .bundles()
+                .format_messages_with_report_sync(&keys, &mut errors)
+                .unwrap();
+            // The access key comes from "en-US", and the missing message
+            // from the last resort.
+            assert_eq!(reports[0].value.as_ref().unwrap().attributes.len(), 2);
+            assert_eq!(
+                reports[1].value.as_ref().unwrap().value.as_deref(),
+                Some("missing-message")
+            );
+            assert!(reports[1].is_fallback());
+            errors.len()
+        }
+    });
+    assert_eq!(handle.join().unwrap(), 4);
+    assert_eq!(reported.lock().unwrap().len(), 4);
+
+    let errors = loc
+        .strict()
+        .format_value_sync("hello-world-3", None)
+        .unwrap_err();
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn concurrent_localization_format_sync() {
+    let loc = Arc::new(get_localization(true));
+
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let loc = Arc::clone(&loc);
+            thread::spawn(move || {
+                let bundles = loc.bundles();
+                let mut errors = vec![];
+                let value = bundles
+                    .format_value_sync("hello-world-3", None, &mut errors)
+                    .unwrap()
+                    .map(Cow::into_owned);
+                // `pl` is missing the message.
+                assert_eq!(errors.len(), 1);
+                value
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        assert_eq!(
+            handle.join().unwrap().as_deref(),
+            Some("Hello World 3 [en]")
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_localization_format_async() {
+    let loc = Arc::new(get_localization(false));
+
+    let tasks: Vec<_> = (0..8)
+        .map(|idx| {
+            let loc = Arc::clone(&loc);
+            tokio::spawn(async move {
+                let bundles = loc.bundles();
+                let mut args = FluentArgs::new();
+                args.set("userName", format!("User {}", idx));
+                let mut errors = vec![];
+
+                let value = bundles
+                    .format_value("message-4", Some(&args), &mut errors)
+                    .await
+                    .map(Cow::into_owned);
+
+                let keys = vec![
+                    L10nKey {
+                        id: "message-1".into(),
+                        args: None,
+                        attributes: None,
+                    },
+                    L10nKey {
+                        id: "hello-world-3".into(),
+                        args: None,
+                        attributes: None,
+                    },
+                ];
+                let messages: Vec<_> = bundles
+                    .format_messages(&keys, &mut errors)
+                    .await
+                    .into_iter()
+                    .map(|msg| msg.and_then(|msg| msg.value).map(Cow::into_owned))
+                    .collect();
+                (idx, value, messages)
+            })
+        })
+        .collect();
+
+    for task in tasks {
+        let (idx, value, messages) = task.await.unwrap();
+        assert_eq!(value, Some(format!("Hello, User {}. [pl]", idx)));
+        assert_eq!(
+            messages,
+            vec![
+                Some("Message 1 Value [pl]".to_string()),
+                Some("Hello World 3 [en]".to_string()),
+            ]
+        );
+    }
+
+    let mut errors = vec![];
+    assert!(loc
+        .bundles()
+        .format_value_sync("hello-world", None, &mut errors)
+        .is_err());
+}
+
+#[tokio::test]
+async fn concurrent_localization_locale_changes_keep_bundles() {
+    let mut loc = Localization::with_env(
+        vec!["test.ftl".into(), "test2.ftl".into()],
+        false,
+        vec![langid!("pl"), langid!("en-US")],
+        RecordingResourceManager::default(),
+    );
+    let mut errors = vec![];
+
+    let value = loc
+        .bundles()
+        .format_value("hello-world-3", None, &mut errors)
+        .await;
+    assert_eq!(value.as_deref(), Some("Hello World 3 [en]"));
+
+    // The bundle of "en-US" is kept, and only "pl" is generated again.
+    loc.update_provider(|locales| locales.remove(0));
+    let value = loc
+        .bundles()
+        .format_value("hello-world", None, &mut errors)
+        .await;
+    assert_eq!(value.as_deref(), Some("Hello World [en]"));
+    loc.update_provider(|locales| locales.insert(0, langid!("pl")));
+    let bundles = Arc::clone(loc.bundles());
+    let value = bundles.format_value("hello-world", None, &mut errors).await;
+    assert_eq!(value.as_deref(), Some("Hello World [pl]"));
+
+    assert_eq!(
+        *loc.generator().calls.lock().unwrap(),
+        vec![vec![langid!("pl"), langid!("en-US")], vec![langid!("pl")]]
+    );
+}
+
+#[tokio::test]
+async fn concurrent_localization_prefetch() {
+    let mut loc = Localization::with_env(
+        vec!["test.ftl".into()],
+        true,
+        vec![langid!("pl"), langid!("en-US")],
+        PrefetchingResourceManager::default(),
+    );
+    loc.prefetch_sync().unwrap();
+    assert!(loc.generator().prefetched.swap(false, Ordering::SeqCst));
+
+    loc.set_async();
+    assert_eq!(
+        loc.prefetch_sync(),
+        Err(LocalizationError::SyncRequestInAsyncMode)
+    );
+    loc.prefetch_async().await;
+    assert!(loc.generator().prefetched.load(Ordering::SeqCst));
+}
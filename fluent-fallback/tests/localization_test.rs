@@ -6,12 +6,12 @@ use fluent_bundle::{
     FluentArgs, FluentBundle, FluentError, FluentResource,
 };
 use fluent_fallback::{
-    env::LocalesProvider,
-    generator::{BundleGenerator, FluentBundleResult},
-    types::{L10nKey, ResourceId},
-    Localization, LocalizationError,
+    env::{LocalesProvider, NegotiatedLocales, NegotiationStrategy},
+    generator::{BundleGenerator, BundleIterator, FluentBundleResult, IterStream},
+    types::{FallbackAttempt, FallbackReason, L10nKey, ResourceId, ResourceType, ToResourceId},
+    LastResort, Localization, LocalizationChange, LocalizationError,
 };
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use unic_langid::{langid, LanguageIdentifier};
 
@@ -56,6 +56,7 @@ impl LocalesProvider for Locales {
 struct BundleIter {
     locales: <Vec<LanguageIdentifier> as IntoIterator>::IntoIter,
     res_ids: Vec<ResourceId>,
+    generated: Option<Rc<RefCell<Vec<LanguageIdentifier>>>>,
 }
 
 impl Iterator for BundleIter {
@@ -63,6 +64,9 @@ impl Iterator for BundleIter {
 
     fn next(&mut self) -> Option<Self::Item> {
         let locale = self.locales.next()?;
+        if let Some(generated) = &self.generated {
+            generated.borrow_mut().push(locale.clone());
+        }
 
         let mut bundle = FluentBundle::new(vec![locale.clone()]);
         bundle.set_use_isolating(false);
@@ -71,7 +75,11 @@ impl Iterator for BundleIter {
 
         for res_id in &self.res_ids {
             let full_path = format!("./tests/resources/{}/{}", locale, res_id);
-            let source = fs::read_to_string(full_path).unwrap();
+            let source = match fs::read_to_string(full_path) {
+                Ok(source) => source,
+                Err(_) if res_id.is_optional() => continue,
+                Err(err) => panic!("{}", err),
+            };
             let res = match FluentResource::try_new(source) {
                 Ok(res) => res,
                 Err((res, err)) => {
@@ -93,34 +101,10 @@ impl futures::Stream for BundleIter {
     type Item = FluentBundleResult<FluentResource>;
 
     fn poll_next(
-        mut self: std::pin::Pin<&mut Self>,
+        self: std::pin::Pin<&mut Self>,
         _cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Option<Self::Item>> {
-        if let Some(locale) = self.locales.next() {
-            let mut bundle = FluentBundle::new(vec![locale.clone()]);
-            bundle.set_use_isolating(false);
-
-            let mut errors = vec![];
-            for res_id in &self.res_ids {
-                let full_path = format!("./tests/resources/{}/{}", locale, res_id.value);
-                let source = fs::read_to_string(full_path).unwrap();
-                let res = match FluentResource::try_new(source) {
-                    Ok(res) => res,
-                    Err((res, err)) => {
-                        errors.extend(err.into_iter().map(Into::into));
-                        res
-                    }
-                };
-                bundle.add_resource(res).unwrap();
-            }
-            if errors.is_empty() {
-                Some(Ok(bundle)).into()
-            } else {
-                Some(Err((bundle, errors))).into()
-            }
-        } else {
-            None.into()
-        }
+        self.get_mut().next().into()
     }
 }
 
@@ -128,16 +112,50 @@ struct ResourceManager;
 
 impl BundleGenerator for ResourceManager {
     type Resource = FluentResource;
+    type Error = FluentError;
     type LocalesIter = std::vec::IntoIter<LanguageIdentifier>;
     type Iter = BundleIter;
     type Stream = BundleIter;
 
     fn bundles_iter(&self, locales: Self::LocalesIter, res_ids: Vec<ResourceId>) -> Self::Iter {
-        BundleIter { locales, res_ids }
+        BundleIter {
+            locales,
+            res_ids,
+            generated: None,
+        }
     }
 
     fn bundles_stream(&self, locales: Self::LocalesIter, res_ids: Vec<ResourceId>) -> Self::Stream {
-        BundleIter { locales, res_ids }
+        BundleIter {
+            locales,
+            res_ids,
+            generated: None,
+        }
+    }
+}
+
+impl BundleIterator for BundleIter {}
+
+/// A generator which only loads bundles synchronously.
+struct SyncResourceManager;
+
+impl BundleGenerator for SyncResourceManager {
+    type Resource = FluentResource;
+    type Error = FluentError;
+    type LocalesIter = std::vec::IntoIter<LanguageIdentifier>;
+    type Iter = BundleIter;
+    type Stream = IterStream<BundleIter>;
+
+    fn bundles_iter(&self, locales: Self::LocalesIter, res_ids: Vec<ResourceId>) -> Self::Iter {
+        BundleIter {
+            locales,
+            res_ids,
+            generated: None,
+        }
+    }
+
+    fn bundles_stream(&self, locales: Self::LocalesIter, res_ids: Vec<ResourceId>) -> Self::Stream {
+        self.bundles_iter(locales, res_ids).into()
     }
 }
 
@@ -169,6 +187,98 @@ fn localization_format() {
     assert_eq!(errors.len(), 4);
 }
 
+#[tokio::test]
+async fn localization_sync_generator_async() {
+    let resource_ids: Vec<ResourceId> = vec!["test.ftl".into(), "test2.ftl".into()];
+    let locales = Locales::new(vec![langid!("pl"), langid!("en-US")]);
+
+    let mut loc = Localization::with_env(resource_ids, false, locales, SyncResourceManager);
+    assert_eq!(
+        loc.prefetch_sync(),
+        Err(LocalizationError::SyncRequestInAsyncMode)
+    );
+    loc.prefetch_async().await;
+
+    let mut errors = vec![];
+    let value = loc
+        .bundles()
+        .format_value("hello-world-3", None, &mut errors)
+        .await;
+    assert_eq!(value, Some(Cow::Borrowed("Hello World 3 [en]")));
+}
+
+#[test]
+fn localization_resource_id_changes() {
+    let resource_ids: Vec<ResourceId> = vec!["test.ftl".into()];
+    let locales = Locales::new(vec![langid!("pl"), langid!("en-US")]);
+
+    let mut loc = Localization::with_env(resource_ids, true, locales, ResourceManager);
+    let bundles = loc.bundles().clone();
+
+    // Changes which don't affect the resources keep the bundles.
+    loc.add_resource_id("test.ftl");
+    assert_eq!(loc.remove_resource_id(ResourceId::from("test2.ftl")), 1);
+    assert!(Rc::ptr_eq(loc.bundles(), &bundles));
+
+    loc.add_resource_id("test2.ftl".to_resource_id(ResourceType::Optional));
+    assert!(!Rc::ptr_eq(loc.bundles(), &bundles));
+
+    // Changing the type of a resource affects the bundles.
+    let bundles = loc.bundles().clone();
+    loc.add_resource_ids(vec!["test.ftl".into(), "test2.ftl".into()]);
+    assert!(!Rc::ptr_eq(loc.bundles(), &bundles));
+
+    let mut errors = vec![];
+    let value = loc
+        .bundles()
+        .format_value_sync("hello-world-3", None, &mut errors)
+        .unwrap();
+    assert_eq!(value, Some(Cow::Borrowed("Hello World 3 [en]")));
+}
+
+#[test]
+fn localization_subscribe() {
+    let resource_ids: Vec<ResourceId> = vec!["test.ftl".into()];
+    let mut locales = Locales::new(vec![langid!("en-US")]);
+
+    let mut loc = Localization::with_env(resource_ids, true, locales.clone(), ResourceManager);
+    let changes = Rc::new(RefCell::new(vec![]));
+    let observer = changes.clone();
+    let id = loc
+        .subscribe(move |change: &LocalizationChange| observer.borrow_mut().push(change.clone()));
+
+    loc.add_resource_ids(vec!["test.ftl".into(), "test2.ftl".into()]);
+    loc.remove_resource_id(ResourceId::from("test.ftl"));
+    // No-op changes aren't reported.
+    loc.remove_resource_id(ResourceId::from("test.ftl"));
+    assert_eq!(
+        *changes.borrow(),
+        vec![
+            LocalizationChange::ResourcesAdded(vec!["test2.ftl".into()]),
+            LocalizationChange::ResourcesRemoved(vec!["test.ftl".into()]),
+        ]
+    );
+
+    // Locale changes are detected by `on_change`, even before the bundles
+    // were generated, but not by the setters.
+    changes.borrow_mut().clear();
+    loc.on_change();
+    assert!(changes.borrow().is_empty());
+
+    locales.insert(0, langid!("pl"));
+    loc.set_attribute_fallback(true);
+    assert!(changes.borrow().is_empty());
+    loc.on_change();
+    assert_eq!(*changes.borrow(), vec![LocalizationChange::Locales]);
+    loc.on_change();
+    assert_eq!(changes.borrow().len(), 1);
+
+    assert!(loc.unsubscribe(id));
+    assert!(!loc.unsubscribe(id));
+    loc.add_resource_id("test.ftl");
+    assert_eq!(changes.borrow().len(), 1);
+}
+
 #[test]
 fn localization_on_change() {
     let resource_ids: Vec<ResourceId> = vec!["test.ftl".into(), "test2.ftl".into()];
@@ -195,6 +305,910 @@ fn localization_on_change() {
     assert_eq!(value, Some(Cow::Borrowed("Hello World [pl]")));
 }
 
+/// A generator recording the locales of the bundles it was asked for, and
+/// of the bundles it generated.
+#[derive(Default)]
+struct RecordingResourceManager {
+    calls: RefCell<Vec<Vec<LanguageIdentifier>>>,
+    generated: Rc<RefCell<Vec<LanguageIdentifier>>>,
+}
+
+impl BundleGenerator for RecordingResourceManager {
+    type Resource = FluentResource;
+    type Error = FluentError;
+    type LocalesIter = std::vec::IntoIter<LanguageIdentifier>;
+    type Iter = BundleIter;
+    type Stream = BundleIter;
+
+    fn bundles_iter(&self, locales: Self::LocalesIter, res_ids: Vec<ResourceId>) -> Self::Iter {
+        let locales: Vec<_> = locales.collect();
+        self.calls.borrow_mut().push(locales.clone());
+        BundleIter {
+            locales: locales.into_iter(),
+            res_ids,
+            generated: Some(self.generated.clone()),
+        }
+    }
+
+    fn bundles_stream(&self, locales: Self::LocalesIter, res_ids: Vec<ResourceId>) -> Self::Stream {
+        self.bundles_iter(locales, res_ids)
+    }
+
+    fn has_resource(&self, locale: &LanguageIdentifier, res_id: &ResourceId) -> bool {
+        let full_path = format!("./tests/resources/{}/{}", locale, res_id);
+        std::path::Path::new(&full_path).exists()
+    }
+}
+
+#[test]
+fn localization_locale_changes_keep_bundles() {
+    let resource_ids: Vec<ResourceId> = vec!["test.ftl".into(), "test2.ftl".into()];
+    let mut loc = Localization::with_env(
+        resource_ids,
+        true,
+        vec![langid!("pl"), langid!("en-US")],
+        RecordingResourceManager::default(),
+    );
+    let format = |loc: &Localization<_, _>, id| {
+        let mut errors = vec![];
+        loc.bundles()
+            .format_value_sync(id, None, &mut errors)
+            .unwrap()
+            .map(Cow::into_owned)
+    };
+
+    assert_eq!(
+        format(&loc, "hello-world-3").as_deref(),
+        Some("Hello World 3 [en]")
+    );
+    assert_eq!(loc.generator().calls.borrow().len(), 1);
+
+    // The bundle of "en-US" is kept, and only "pl" is generated again.
+    loc.update_provider(|locales| locales.remove(0));
+    assert_eq!(
+        format(&loc, "hello-world").as_deref(),
+        Some("Hello World [en]")
+    );
+    loc.update_provider(|locales| locales.insert(0, langid!("pl")));
+    assert_eq!(
+        format(&loc, "hello-world").as_deref(),
+        Some("Hello World [pl]")
+    );
+    assert_eq!(
+        format(&loc, "hello-world-3").as_deref(),
+        Some("Hello World 3 [en]")
+    );
+
+    // Settings don't affect the bundles.
+    loc.set_last_resort(LastResort::Id);
+    assert_eq!(format(&loc, "missing").as_deref(), Some("missing"));
+    assert_eq!(
+        *loc.generator().calls.borrow(),
+        vec![vec![langid!("pl"), langid!("en-US")], vec![langid!("pl")]]
+    );
+
+    // Every bundle has the resources, so they're all generated again.
+    loc.remove_resource_id(ResourceId::from("test2.ftl"));
+    assert_eq!(
+        format(&loc, "hello-world").as_deref(),
+        Some("Hello World [pl]")
+    );
+    assert_eq!(
+        loc.generator().calls.borrow().last(),
+        Some(&vec![langid!("pl"), langid!("en-US")])
+    );
+}
+
+#[test]
+fn localization_resource_changes_keep_bundles() {
+    let resource_ids: Vec<ResourceId> = vec!["test.ftl".into(), "test2.ftl".into()];
+    let mut loc = Localization::with_env(
+        resource_ids,
+        true,
+        vec![langid!("pl"), langid!("en-US")],
+        RecordingResourceManager::default(),
+    );
+    let format = |loc: &Localization<_, _>, id| {
+        let mut errors = vec![];
+        loc.bundles()
+            .format_value_sync(id, None, &mut errors)
+            .unwrap()
+            .map(Cow::into_owned)
+    };
+
+    assert_eq!(
+        format(&loc, "hello-world-3").as_deref(),
+        Some("Hello World 3 [en]")
+    );
+
+    // "extra.ftl" is only available in "pl", so only its bundle changes.
+    loc.add_resource_id("extra.ftl".to_resource_id(ResourceType::Optional));
+    assert_eq!(format(&loc, "extra").as_deref(), Some("Extra [pl]"));
+    assert_eq!(
+        format(&loc, "hello-world-3").as_deref(),
+        Some("Hello World 3 [en]")
+    );
+    loc.remove_resource_id(ResourceId::from("extra.ftl"));
+    assert_eq!(format(&loc, "extra"), None);
+
+    // An optional resource missing in every locale changes no bundle.
+    loc.add_resource_id("missing.ftl".to_resource_id(ResourceType::Optional));
+    assert_eq!(
+        format(&loc, "hello-world-3").as_deref(),
+        Some("Hello World 3 [en]")
+    );
+    assert_eq!(
+        *loc.generator().generated.borrow(),
+        vec![
+            langid!("pl"),
+            langid!("en-US"),
+            langid!("pl"),
+            langid!("pl")
+        ]
+    );
+}
+
+#[test]
+fn localization_on_change_keeps_bundles() {
+    // There are no resources for "de", so its bundle is empty.
+    let resource_ids: Vec<ResourceId> = vec![
+        "test.ftl".to_resource_id(ResourceType::Optional),
+        "test2.ftl".to_resource_id(ResourceType::Optional),
+    ];
+    let mut locales = Locales::new(vec![langid!("en-US"), langid!("de")]);
+    let mut loc = Localization::with_env(
+        resource_ids,
+        true,
+        locales.clone(),
+        RecordingResourceManager::default(),
+    );
+    let format = |loc: &Localization<_, _>, id| {
+        let mut errors = vec![];
+        loc.bundles()
+            .format_value_sync(id, None, &mut errors)
+            .unwrap()
+            .map(Cow::into_owned)
+    };
+
+    assert_eq!(format(&loc, "missing"), None);
+
+    // `Locales` doesn't support subsets, so "pl" is generated along with
+    // all the locales, which stop at the next one, "en-US", and the bundles
+    // of "en-US" and "de" are kept.
+    locales.insert(0, langid!("pl"));
+    loc.on_change();
+    assert_eq!(format(&loc, "missing"), None);
+    assert_eq!(
+        format(&loc, "hello-world").as_deref(),
+        Some("Hello World [pl]")
+    );
+    assert_eq!(
+        *loc.generator().calls.borrow(),
+        vec![
+            vec![langid!("en-US"), langid!("de")],
+            vec![langid!("pl"), langid!("en-US"), langid!("de")]
+        ]
+    );
+    assert_eq!(
+        *loc.generator().generated.borrow(),
+        vec![
+            langid!("en-US"),
+            langid!("de"),
+            langid!("pl"),
+            langid!("en-US")
+        ]
+    );
+
+    // Without a locale change, the resources may have changed, so all the
+    // bundles are generated again.
+    loc.generator().generated.borrow_mut().clear();
+    loc.on_change();
+    assert_eq!(format(&loc, "missing"), None);
+    assert_eq!(
+        *loc.generator().generated.borrow(),
+        vec![langid!("pl"), langid!("en-US"), langid!("de")]
+    );
+}
+
+#[test]
+fn localization_locale_changes_skip_removed_locales() {
+    let resource_ids: Vec<ResourceId> = vec!["test.ftl".into()];
+    let mut loc = Localization::with_env(
+        resource_ids,
+        true,
+        vec![langid!("pl"), langid!("en-US")],
+        RecordingResourceManager::default(),
+    );
+    let mut errors = vec![];
+
+    // Nothing was generated for "pl", so "en-US" isn't taken from the
+    // bundles generated for both.
+    loc.bundles();
+    loc.update_provider(|locales| locales.remove(0));
+    let value = loc
+        .bundles()
+        .format_value_sync("hello-world", None, &mut errors)
+        .unwrap();
+    assert_eq!(value, Some(Cow::Borrowed("Hello World [en]")));
+    assert_eq!(
+        *loc.generator().calls.borrow(),
+        vec![
+            vec![langid!("pl"), langid!("en-US")],
+            vec![langid!("en-US")]
+        ]
+    );
+}
+
+#[test]
+fn localization_negotiated_locales() {
+    let resource_ids: Vec<ResourceId> = vec!["test.ftl".into(), "test2.ftl".into()];
+
+    let locales = NegotiatedLocales::new(
+        vec![langid!("de"), langid!("pl-PL")],
+        vec![langid!("en-US"), langid!("pl")],
+        Some(langid!("en-US")),
+        NegotiationStrategy::Filtering,
+    );
+    let res_mgr = ResourceManager;
+    let mut errors = vec![];
+
+    let mut loc = Localization::with_env(resource_ids, true, locales.clone(), res_mgr);
+
+    let value = loc
+        .bundles()
+        .format_value_sync("hello-world", None, &mut errors)
+        .unwrap();
+    assert_eq!(value, Some(Cow::Borrowed("Hello World [pl]")));
+
+    let changes = Rc::new(RefCell::new(vec![]));
+    let observer = changes.clone();
+    loc.subscribe(move |change: &LocalizationChange| observer.borrow_mut().push(change.clone()));
+
+    assert!(!loc.update_provider(|locales| locales.set_requested(vec![langid!("pl")])));
+    assert!(changes.borrow().is_empty());
+    assert!(loc.update_provider(|locales| locales.set_requested(vec![langid!("de")])));
+    assert_eq!(*changes.borrow(), vec![LocalizationChange::Locales]);
+    assert_eq!(
+        locales.locales().collect::<Vec<_>>(),
+        vec![langid!("en-US")]
+    );
+
+    let value = loc
+        .bundles()
+        .format_value_sync("hello-world", None, &mut errors)
+        .unwrap();
+    assert_eq!(value, Some(Cow::Borrowed("Hello World [en]")));
+
+    // Updates of a clone are applied by `on_change`.
+    assert!(locales.set_available(vec![langid!("de"), langid!("en-US")]));
+    assert_eq!(
+        locales.locales().collect::<Vec<_>>(),
+        vec![langid!("de"), langid!("en-US")]
+    );
+    loc.on_change();
+    assert_eq!(changes.borrow().len(), 2);
+}
+
+#[test]
+fn localization_format_values_with_report() {
+    let resource_ids: Vec<ResourceId> = vec!["test.ftl".into(), "test2.ftl".into()];
+    let locales = Locales::new(vec![langid!("pl"), langid!("en-US")]);
+    let res_mgr = ResourceManager;
+    let mut errors = vec![];
+
+    let loc = Localization::with_env(resource_ids, true, locales, res_mgr);
+    let bundles = loc.bundles();
+
+    let keys = vec![
+        L10nKey::from("hello-world"),
+        L10nKey::from("hello-world-3"),
+        L10nKey::from("message-3"),
+        L10nKey::from("missing-message"),
+    ];
+    let reports = bundles
+        .format_values_with_report_sync(&keys, &mut errors)
+        .unwrap();
+
+    assert_eq!(reports[0].value, Some(Cow::Borrowed("Hello World [pl]")));
+    assert_eq!(reports[0].locale, Some(langid!("pl")));
+    assert!(reports[0].attempts.is_empty());
+    assert!(!reports[0].is_fallback());
+
+    assert_eq!(reports[1].value, Some(Cow::Borrowed("Hello World 3 [en]")));
+    assert_eq!(reports[1].locale, Some(langid!("en-US")));
+    assert_eq!(
+        reports[1].attempts,
+        vec![FallbackAttempt {
+            locale: langid!("pl"),
+            reason: FallbackReason::MissingMessage,
+        }]
+    );
+    assert!(reports[1].is_fallback());
+
+    assert_eq!(reports[2].value, None);
+    assert_eq!(reports[2].locale, None);
+    assert_eq!(
+        reports[2].attempts,
+        vec![
+            FallbackAttempt {
+                locale: langid!("pl"),
+                reason: FallbackReason::MissingValue,
+            },
+            FallbackAttempt {
+                locale: langid!("en-US"),
+                reason: FallbackReason::MissingValue,
+            },
+        ]
+    );
+
+    assert_eq!(reports[3].value, None);
+    assert_eq!(reports[3].attempts.len(), 2);
+
+    // The errors are the same as the ones reported by `format_values`.
+    let mut values_errors = vec![];
+    bundles
+        .format_values_sync(&keys, &mut values_errors)
+        .unwrap();
+    assert_eq!(errors, values_errors);
+}
+
+#[tokio::test]
+async fn localization_format_messages_with_report() {
+    let resource_ids: Vec<ResourceId> = vec!["test.ftl".into(), "test2.ftl".into()];
+    let locales = Locales::new(vec![langid!("pl"), langid!("en-US")]);
+    let res_mgr = ResourceManager;
+    let mut errors = vec![];
+
+    let loc = Localization::with_env(resource_ids, false, locales, res_mgr);
+    let bundles = loc.bundles();
+
+    let keys = vec![L10nKey::from("message-3"), L10nKey::from("hello-world-3")];
+    let reports = bundles
+        .format_messages_with_report(&keys, &mut errors)
+        .await;
+
+    // Messages without a value are still messages.
+    let message = reports[0].value.as_ref().unwrap();
+    assert_eq!(message.value, None);
+    assert_eq!(message.attributes[0].value, "Message 3 Attribute [pl]");
+    assert_eq!(reports[0].locale, Some(langid!("pl")));
+
+    assert_eq!(reports[1].locale, Some(langid!("en-US")));
+    assert_eq!(
+        reports[1].attempts,
+        vec![FallbackAttempt {
+            locale: langid!("pl"),
+            reason: FallbackReason::MissingMessage,
+        }]
+    );
+    assert_eq!(
+        errors,
+        vec![LocalizationError::MissingMessage {
+            id: "hello-world-3".to_string(),
+            locale: Some(langid!("pl")),
+        }]
+    );
+}
+
+#[test]
+fn localization_last_resort() {
+    let resource_ids: Vec<ResourceId> = vec!["test.ftl".into(), "test2.ftl".into()];
+    let locales = Locales::new(vec![langid!("pl"), langid!("en-US")]);
+    let res_mgr = ResourceManager;
+
+    let mut loc = Localization::with_env(resource_ids, true, locales, res_mgr);
+    let keys = vec![
+        L10nKey::from("hello-world"),
+        L10nKey::from("missing-message"),
+    ];
+
+    loc.set_last_resort(LastResort::Id);
+    let mut errors = vec![];
+    let values = loc
+        .bundles()
+        .format_values_sync(&keys, &mut errors)
+        .unwrap();
+    assert_eq!(
+        values,
+        vec![
+            Some(Cow::Borrowed("Hello World [pl]")),
+            Some(Cow::Borrowed("missing-message")),
+        ]
+    );
+    // The missing message is still reported.
+    assert_eq!(
+        errors.last(),
+        Some(&LocalizationError::MissingMessage {
+            id: "missing-message".to_string(),
+            locale: None,
+        })
+    );
+
+    // Reports agree with the values, and tell the last resort apart.
+    let mut report_errors = vec![];
+    let reports = loc
+        .bundles()
+        .format_values_with_report_sync(&keys, &mut report_errors)
+        .unwrap();
+    assert_eq!(reports[1].value, values[1]);
+    assert_eq!(reports[1].locale, None);
+    assert_eq!(reports[1].attempts.len(), 2);
+    assert!(reports[1].is_fallback());
+    assert_eq!(report_errors, errors);
+
+    fn callback(id: &str, args: Option<&FluentArgs>) -> String {
+        let count = args.map_or(0, |args| args.iter().count());
+        format!("<{} with {} args>", id, count)
+    }
+    loc.set_last_resort(LastResort::Callback(callback));
+    let mut args = FluentArgs::new();
+    args.set("name", "John");
+    let mut errors = vec![];
+    let value = loc
+        .bundles()
+        .format_value_sync("missing-message", Some(&args), &mut errors)
+        .unwrap();
+    assert_eq!(value, Some(Cow::Borrowed("<missing-message with 1 args>")));
+    assert_eq!(errors.len(), 3);
+
+    let mut bundle = FluentBundle::new(vec![langid!("en-US")]);
+    bundle.set_use_isolating(false);
+    let res = FluentResource::try_new(
+        "missing-message = Default\n    .title = Default title".to_string(),
+    )
+    .unwrap();
+    bundle.add_resource(res).unwrap();
+    loc.set_last_resort(LastResort::Bundle(bundle));
+
+    let mut errors = vec![];
+    let messages = loc
+        .bundles()
+        .format_messages_sync(&keys, &mut errors)
+        .unwrap();
+    let message = messages[1].as_ref().unwrap();
+    assert_eq!(message.value, Some(Cow::Borrowed("Default")));
+    assert_eq!(message.attributes[0].value, "Default title");
+
+    // Keys missing from the last resort bundle stay untranslated.
+    let value = loc
+        .bundles()
+        .format_value_sync("other-message", None, &mut errors)
+        .unwrap();
+    assert_eq!(value, None);
+}
+
+#[tokio::test]
+async fn localization_last_resort_async() {
+    let resource_ids: Vec<ResourceId> = vec!["test.ftl".into(), "test2.ftl".into()];
+    let locales = Locales::new(vec![langid!("pl"), langid!("en-US")]);
+    let res_mgr = ResourceManager;
+
+    let mut loc = Localization::with_env(resource_ids, false, locales, res_mgr);
+    loc.set_last_resort(LastResort::Id);
+
+    let mut errors = vec![];
+    let value = loc
+        .bundles()
+        .format_value("missing-message", None, &mut errors)
+        .await;
+    assert_eq!(value, Some(Cow::Borrowed("missing-message")));
+
+    let keys = vec![L10nKey::from("missing-message")];
+    let messages = loc.bundles().format_messages(&keys, &mut errors).await;
+    let message = messages[0].as_ref().unwrap();
+    assert_eq!(message.value, Some(Cow::Borrowed("missing-message")));
+    assert!(message.attributes.is_empty());
+
+    let reports = loc
+        .bundles()
+        .format_messages_with_report(&keys, &mut errors)
+        .await;
+    let message = reports[0].value.as_ref().unwrap();
+    assert_eq!(message.value, Some(Cow::Borrowed("missing-message")));
+    assert_eq!(reports[0].locale, None);
+}
+
+#[test]
+fn localization_format_selected_attributes() {
+    let resource_ids: Vec<ResourceId> = vec!["test.ftl".into(), "test2.ftl".into()];
+    let locales = Locales::new(vec![langid!("pl"), langid!("en-US")]);
+    let res_mgr = ResourceManager;
+
+    let loc = Localization::with_env(resource_ids, true, locales, res_mgr);
+    let bundles = loc.bundles();
+    let keys = vec![
+        L10nKey {
+            id: "message-5".into(),
+            args: None,
+            attributes: Some(vec!["label".into()]),
+        },
+        L10nKey {
+            id: "message-5".into(),
+            args: None,
+            attributes: Some(vec!["accesskey".into(), "label".into()]),
+        },
+    ];
+
+    let mut errors = vec![];
+    let messages = bundles.format_messages_sync(&keys, &mut errors).unwrap();
+
+    // Only the requested attributes are formatted, in the requested order.
+    let message = messages[0].as_ref().unwrap();
+    assert_eq!(message.value, Some(Cow::Borrowed("Message 5 Value [pl]")));
+    assert_eq!(message.attributes.len(), 1);
+    assert_eq!(message.attributes[0].value, "Message 5 Label [pl]");
+
+    // The "pl" message lacks the access key, so only it falls back to "en-US".
+    let message = messages[1].as_ref().unwrap();
+    assert_eq!(message.value, Some(Cow::Borrowed("Message 5 Value [pl]")));
+    let names: Vec<_> = message.attributes.iter().map(|attr| &attr.name).collect();
+    assert_eq!(names, vec!["accesskey", "label"]);
+    assert_eq!(message.attributes[0].value, "M");
+    assert_eq!(message.attributes[1].value, "Message 5 Label [pl]");
+    assert_eq!(
+        errors,
+        vec![LocalizationError::MissingAttribute {
+            id: "message-5".to_string(),
+            attribute: "accesskey".to_string(),
+            locale: langid!("pl"),
+        }]
+    );
+
+    let mut errors = vec![];
+    let reports = bundles
+        .format_messages_with_report_sync(&keys, &mut errors)
+        .unwrap();
+    assert!(!reports[0].is_fallback());
+    assert_eq!(reports[1].locale, Some(langid!("pl")));
+    assert_eq!(
+        reports[1].attempts,
+        vec![FallbackAttempt {
+            locale: langid!("pl"),
+            reason: FallbackReason::MissingAttribute,
+        }]
+    );
+}
+
+#[test]
+fn localization_format_missing_attributes() {
+    let resource_ids: Vec<ResourceId> = vec!["test.ftl".into(), "test2.ftl".into()];
+    let locales = Locales::new(vec![langid!("pl"), langid!("en-US")]);
+    let res_mgr = ResourceManager;
+
+    let loc = Localization::with_env(resource_ids, true, locales, res_mgr);
+    let keys = vec![L10nKey {
+        id: "message-5".into(),
+        args: None,
+        attributes: Some(vec!["title".into(), "label".into()]),
+    }];
+
+    // The message is formatted with the attributes which it has.
+    let mut errors = vec![];
+    let messages = loc
+        .bundles()
+        .format_messages_sync(&keys, &mut errors)
+        .unwrap();
+    let message = messages[0].as_ref().unwrap();
+    assert_eq!(message.value, Some(Cow::Borrowed("Message 5 Value [pl]")));
+    assert_eq!(message.attributes.len(), 1);
+    assert_eq!(message.attributes[0].value, "Message 5 Label [pl]");
+    assert_eq!(
+        errors,
+        vec![
+            LocalizationError::MissingAttribute {
+                id: "message-5".to_string(),
+                attribute: "title".to_string(),
+                locale: langid!("pl"),
+            },
+            LocalizationError::MissingAttribute {
+                id: "message-5".to_string(),
+                attribute: "title".to_string(),
+                locale: langid!("en-US"),
+            },
+        ]
+    );
+}
+
+#[test]
+fn localization_attribute_fallback() {
+    let resource_ids: Vec<ResourceId> = vec!["test.ftl".into(), "test2.ftl".into()];
+    let locales = Locales::new(vec![langid!("pl"), langid!("en-US")]);
+    let res_mgr = ResourceManager;
+
+    let mut loc = Localization::with_env(resource_ids, true, locales, res_mgr);
+    let keys = vec![L10nKey::from("message-5"), L10nKey::from("message-1")];
+
+    // By default, the attributes come from the translation alone.
+    let mut errors = vec![];
+    let messages = loc
+        .bundles()
+        .format_messages_sync(&keys, &mut errors)
+        .unwrap();
+    assert_eq!(messages[0].as_ref().unwrap().attributes.len(), 1);
+    assert!(errors.is_empty());
+
+    loc.set_attribute_fallback(true);
+    let messages = loc
+        .bundles()
+        .format_messages_sync(&keys, &mut errors)
+        .unwrap();
+    let message = messages[0].as_ref().unwrap();
+    assert_eq!(message.value, Some(Cow::Borrowed("Message 5 Value [pl]")));
+    let attributes: Vec<_> = message
+        .attributes
+        .iter()
+        .map(|attr| (attr.name.as_ref(), attr.value.as_ref()))
+        .collect();
+    assert_eq!(
+        attributes,
+        vec![("label", "Message 5 Label [pl]"), ("accesskey", "M")]
+    );
+    // Complete translations are left as they are.
+    assert_eq!(messages[1].as_ref().unwrap().attributes.len(), 1);
+    assert_eq!(
+        errors,
+        vec![LocalizationError::AttributeFallback {
+            id: "message-5".to_string(),
+            attribute: "accesskey".to_string(),
+            locale: langid!("pl"),
+            fallback: langid!("en-US"),
+        }]
+    );
+}
+
+/// A generator which yields a bundle for each of the given sources, whatever
+/// the locales and resources requested, and counts the bundles yielded.
+struct MemoryResourceManager {
+    sources: Vec<(LanguageIdentifier, &'static str)>,
+    yielded: Rc<Cell<usize>>,
+}
+
+impl MemoryResourceManager {
+    fn new(sources: Vec<(LanguageIdentifier, &'static str)>) -> Self {
+        Self {
+            sources,
+            yielded: Default::default(),
+        }
+    }
+}
+
+struct MemoryBundleIter {
+    bundles: std::vec::IntoIter<FluentBundle<FluentResource>>,
+    yielded: Rc<Cell<usize>>,
+}
+
+impl Iterator for MemoryBundleIter {
+    type Item = FluentBundleResult<FluentResource>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bundle = self.bundles.next()?;
+        self.yielded.set(self.yielded.get() + 1);
+        Some(Ok(bundle))
+    }
+}
+
+impl BundleIterator for MemoryBundleIter {}
+
+impl BundleGenerator for MemoryResourceManager {
+    type Resource = FluentResource;
+    type Error = FluentError;
+    type LocalesIter = std::vec::IntoIter<LanguageIdentifier>;
+    type Iter = MemoryBundleIter;
+    type Stream = IterStream<MemoryBundleIter>;
+
+    fn bundles_iter(&self, _: Self::LocalesIter, _: Vec<ResourceId>) -> Self::Iter {
+        let bundles: Vec<_> = self
+            .sources
+            .iter()
+            .map(|(locale, source)| {
+                let mut bundle = FluentBundle::new(vec![locale.clone()]);
+                let res = FluentResource::try_new(source.to_string()).unwrap();
+                bundle.add_resource(res).unwrap();
+                bundle
+            })
+            .collect();
+        MemoryBundleIter {
+            bundles: bundles.into_iter(),
+            yielded: self.yielded.clone(),
+        }
+    }
+
+    fn bundles_stream(&self, locales: Self::LocalesIter, res_ids: Vec<ResourceId>) -> Self::Stream {
+        self.bundles_iter(locales, res_ids).into()
+    }
+}
+
+#[test]
+fn localization_attribute_fallback_same_locale() {
+    // Like a generator combining alternative sources of the resources, this
+    // yields two bundles for "pl".
+    let res_mgr = MemoryResourceManager::new(vec![
+        (langid!("pl"), "key = Value [pl]\n"),
+        (langid!("pl"), "key = Value [pl]\n    .title = Title [pl]\n"),
+        (
+            langid!("en-US"),
+            "key = Value [en]\n    .title = Title [en]\n",
+        ),
+    ]);
+    let locales = Locales::new(vec![langid!("pl"), langid!("en-US")]);
+    let mut loc = Localization::with_env(vec!["test.ftl".into()], true, locales, res_mgr);
+    loc.set_attribute_fallback(true);
+
+    let keys = vec![L10nKey::from("key")];
+    let mut errors = vec![];
+    let messages = loc
+        .bundles()
+        .format_messages_sync(&keys, &mut errors)
+        .unwrap();
+    let message = messages[0].as_ref().unwrap();
+    assert_eq!(message.attributes.len(), 1);
+    assert_eq!(message.attributes[0].value, "Title [en]");
+    assert_eq!(
+        errors,
+        vec![LocalizationError::AttributeFallback {
+            id: "key".to_string(),
+            attribute: "title".to_string(),
+            locale: langid!("pl"),
+            fallback: langid!("en-US"),
+        }]
+    );
+}
+
+#[test]
+fn localization_attribute_fallback_first_following_locale() {
+    let res_mgr = MemoryResourceManager::new(vec![
+        (
+            langid!("pl"),
+            "key = Value [pl]\nother = Other [pl]\n    .title = Title [pl]\n",
+        ),
+        (langid!("de"), "key = Value [de]\n    .title = Title [de]\n"),
+        (
+            langid!("en-US"),
+            "key = Value [en]\n    .title = Title [en]\n    .label = Label [en]\n",
+        ),
+    ]);
+    let yielded = res_mgr.yielded.clone();
+    let locales = Locales::new(vec![langid!("pl"), langid!("de"), langid!("en-US")]);
+    let mut loc = Localization::with_env(vec!["test.ftl".into()], true, locales, res_mgr);
+    loc.set_attribute_fallback(true);
+    let bundles = loc.bundles();
+    let mut errors = vec![];
+
+    // Keys requesting specific attributes don't need more bundles.
+    let keys = vec![L10nKey {
+        id: "other".into(),
+        args: None,
+        attributes: Some(vec!["title".into()]),
+    }];
+    bundles.format_messages_sync(&keys, &mut errors).unwrap();
+    assert_eq!(yielded.get(), 1);
+
+    // The attributes are taken from "de", without resolving "en-US".
+    let keys = vec![L10nKey::from("key")];
+    errors.clear();
+    let messages = bundles.format_messages_sync(&keys, &mut errors).unwrap();
+    let message = messages[0].as_ref().unwrap();
+    assert_eq!(message.attributes.len(), 1);
+    assert_eq!(message.attributes[0].value, "Title [de]");
+    assert_eq!(yielded.get(), 2);
+    assert_eq!(
+        errors,
+        vec![LocalizationError::AttributeFallback {
+            id: "key".to_string(),
+            attribute: "title".to_string(),
+            locale: langid!("pl"),
+            fallback: langid!("de"),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn localization_attribute_fallback_async() {
+    let resource_ids: Vec<ResourceId> = vec!["test.ftl".into(), "test2.ftl".into()];
+    let locales = Locales::new(vec![langid!("pl"), langid!("en-US")]);
+    let res_mgr = ResourceManager;
+
+    let mut loc = Localization::with_env(resource_ids, false, locales, res_mgr);
+    loc.set_attribute_fallback(true);
+
+    let keys = vec![L10nKey::from("message-5")];
+    let mut errors = vec![];
+    let messages = loc.bundles().format_messages(&keys, &mut errors).await;
+    let message = messages[0].as_ref().unwrap();
+    assert_eq!(message.attributes.len(), 2);
+    assert_eq!(message.attributes[1].value, "M");
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn localization_strict() {
+    let resource_ids: Vec<ResourceId> = vec!["test.ftl".into(), "test2.ftl".into()];
+    let locales = Locales::new(vec![langid!("pl"), langid!("en-US")]);
+
+    let mut loc = Localization::with_env(resource_ids, true, locales, ResourceManager);
+    loc.set_last_resort(LastResort::Id);
+    let bundles = loc.bundles().strict();
+
+    let keys = vec![L10nKey::from("hello-world"), L10nKey::from("message-1")];
+    let values = bundles.format_values_sync(&keys).unwrap();
+    assert_eq!(values[0], Some(Cow::Borrowed("Hello World [pl]")));
+
+    // Falling back to another locale, or to the last resort, is an error.
+    let errors = bundles
+        .format_value_sync("hello-world-3", None)
+        .unwrap_err();
+    assert_eq!(
+        errors,
+        vec![LocalizationError::MissingMessage {
+            id: "hello-world-3".to_string(),
+            locale: Some(langid!("pl")),
+        }]
+    );
+    let keys = vec![L10nKey::from("missing-message")];
+    let errors = bundles.format_messages_sync(&keys).unwrap_err();
+    assert_eq!(errors.len(), 3);
+
+    // So are resolver errors.
+    let keys = vec![L10nKey::from("message-4")];
+    let errors = bundles.format_values_sync(&keys).unwrap_err();
+    assert!(matches!(errors[0], LocalizationError::Resolver { .. }));
+}
+
+#[tokio::test]
+async fn localization_strict_async() {
+    let resource_ids: Vec<ResourceId> = vec!["test.ftl".into(), "test2.ftl".into()];
+    let locales = Locales::new(vec![langid!("pl"), langid!("en-US")]);
+
+    let loc = Localization::with_env(resource_ids, false, locales, ResourceManager);
+    let bundles = loc.bundles().strict();
+
+    let value = bundles.format_value("hello-world", None).await.unwrap();
+    assert_eq!(value, Some(Cow::Borrowed("Hello World [pl]")));
+    assert!(bundles.format_value("hello-world-3", None).await.is_err());
+    assert_eq!(
+        bundles.format_value_sync("hello-world", None),
+        Err(vec![LocalizationError::SyncRequestInAsyncMode])
+    );
+}
+
+#[test]
+fn localization_error_sink() {
+    let resource_ids: Vec<ResourceId> = vec!["test.ftl".into(), "test2.ftl".into()];
+    let locales = Locales::new(vec![langid!("pl"), langid!("en-US")]);
+    let res_mgr = ResourceManager;
+
+    let mut loc = Localization::with_env(resource_ids, true, locales, res_mgr);
+    let reported = Rc::new(RefCell::new(vec![]));
+    let sink = reported.clone();
+    loc.set_error_sink(move |error: &LocalizationError| sink.borrow_mut().push(error.to_string()));
+
+    let mut errors = vec![];
+    for _ in 0..2 {
+        let _ = loc
+            .bundles()
+            .format_value_sync("missing-message", None, &mut errors)
+            .unwrap();
+    }
+    // The errors vector receives every error, while the sink only gets each
+    // of them once.
+    assert_eq!(errors.len(), 6);
+    assert_eq!(
+        *reported.borrow(),
+        vec![
+            "[fluent] Missing message in locale pl: missing-message".to_string(),
+            "[fluent] Missing message in locale en-US: missing-message".to_string(),
+            "[fluent] Couldn't find a message: missing-message".to_string(),
+        ]
+    );
+
+    // Distinct errors are still reported.
+    let _ = loc
+        .bundles()
+        .format_value_sync("other-missing-message", None, &mut errors)
+        .unwrap();
+    assert_eq!(reported.borrow().len(), 6);
+}
+
 #[test]
 fn localization_format_value_missing_errors() {
     let resource_ids: Vec<ResourceId> = vec!["test.ftl".into(), "test2.ftl".into()];
@@ -441,11 +1455,12 @@ fn localization_format_missing_argument_error() {
     let keys = vec![L10nKey {
         id: "message-4".into(),
         args: Some(args),
+        attributes: None,
     }];
 
     let msgs = bundles.format_messages_sync(&keys, &mut errors).unwrap();
     assert_eq!(
-        msgs.get(0).unwrap().as_ref().unwrap().value,
+        msgs.first().unwrap().as_ref().unwrap().value,
         Some(Cow::Borrowed("Hello, John. [en]"))
     );
     assert_eq!(errors.len(), 0);
@@ -453,10 +1468,11 @@ fn localization_format_missing_argument_error() {
     let keys = vec![L10nKey {
         id: "message-4".into(),
         args: None,
+        attributes: None,
     }];
     let msgs = bundles.format_messages_sync(&keys, &mut errors).unwrap();
     assert_eq!(
-        msgs.get(0).unwrap().as_ref().unwrap().value,
+        msgs.first().unwrap().as_ref().unwrap().value,
         Some(Cow::Borrowed("Hello, {$userName}. [en]"))
     );
     assert_eq!(
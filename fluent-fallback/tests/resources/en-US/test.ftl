@@ -2,3 +2,7 @@ hello-world = Hello World [en]
 
 message-1 = Message 1 Value [en]
     .attr1 = Message 1 Attribute [en]
+
+message-5 = Message 5 Value [en]
+    .label = Message 5 Label [en]
+    .accesskey = M
@@ -2,3 +2,6 @@ hello-world = Hello World [pl]
 
 message-1 = Message 1 Value [pl]
     .attr1 = Message 1 Attribute [pl]
+
+message-5 = Message 5 Value [pl]
+    .label = Message 5 Label [pl]
@@ -90,10 +90,16 @@ fn main() {
     );
 
     // 5. Get a bundle for given paths and locales.
-    let bundle = mgr.get_bundle(
+    let bundle = match mgr.get_bundle(
         resolved_locales.into_iter().map(|s| s.to_owned()).collect(),
         resources,
-    );
+    ) {
+        Ok(bundle) => bundle,
+        Err((bundle, errors)) => {
+            eprintln!("Errors while loading resources: {:?}", errors);
+            bundle
+        }
+    };
 
     // 6. Check if the input is provided.
     match args.get(1) {
@@ -0,0 +1,121 @@
+//! Resources embedded in the binary, for example with [`include_str!`].
+use crate::fetcher::FileFetcher;
+use crate::loader;
+use crate::resource_manager::ResourceManager;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::io;
+use std::iter::FromIterator;
+use unic_langid::LanguageIdentifier;
+
+/// The path scheme under which embedded resources are stored.
+const PATH_SCHEME: &str = "{locale}/{res_id}";
+
+/// A map of resource sources by locale and resource id, held in memory.
+///
+/// It's a [`FileFetcher`] serving paths of the `"{locale}/{res_id}"` scheme,
+/// and [`ResourceManager::embedded`] creates a manager generating bundles
+/// out of it, both synchronously and asynchronously.
+///
+/// # Example
+///
+/// ```
+/// use fluent_fallback::Localization;
+/// use fluent_resmgr::{EmbeddedResources, ResourceManager};
+/// use unic_langid::langid;
+///
+/// let mut resources = EmbeddedResources::new();
+/// // Usually `include_str!("../resources/en-US/main.ftl")`.
+/// resources.insert(langid!("en-US"), "main.ftl", "hello = Hello");
+/// resources.insert(langid!("pl"), "main.ftl", "hello = Witaj");
+///
+/// let loc = Localization::with_env(
+///     vec!["main.ftl".into()],
+///     true,
+///     vec![langid!("pl")],
+///     ResourceManager::embedded(resources),
+/// );
+///
+/// let mut errors = vec![];
+/// let value = loc
+///     .bundles()
+///     .format_value_sync("hello", None, &mut errors)
+///     .expect("Failed to format a value.");
+/// assert_eq!(value.as_deref(), Some("Witaj"));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct EmbeddedResources {
+    resources: HashMap<String, &'static str>,
+}
+
+impl EmbeddedResources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the source of a resource, returning the source it replaces, if any.
+    pub fn insert(
+        &mut self,
+        locale: LanguageIdentifier,
+        res_id: &str,
+        source: &'static str,
+    ) -> Option<&'static str> {
+        self.resources.insert(get_path(&locale, res_id), source)
+    }
+
+    /// Returns the source of a resource, if it's available.
+    pub fn get(&self, locale: &LanguageIdentifier, res_id: &str) -> Option<&'static str> {
+        self.resources.get(&get_path(locale, res_id)).copied()
+    }
+}
+
+fn get_path(locale: &LanguageIdentifier, res_id: &str) -> String {
+    loader::get_path(PATH_SCHEME, &locale.to_string(), res_id)
+}
+
+impl<'a> FromIterator<(LanguageIdentifier, &'a str, &'static str)> for EmbeddedResources {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (LanguageIdentifier, &'a str, &'static str)>,
+    {
+        let mut resources = Self::new();
+        for (locale, res_id, source) in iter {
+            resources.insert(locale, res_id, source);
+        }
+        resources
+    }
+}
+
+#[async_trait(?Send)]
+impl FileFetcher for EmbeddedResources {
+    fn fetch_sync(&self, path: &str) -> io::Result<String> {
+        self.resources
+            .get(path)
+            .map(|source| source.to_string())
+            .ok_or_else(|| io::ErrorKind::NotFound.into())
+    }
+
+    async fn fetch(&self, path: &str) -> io::Result<String> {
+        self.fetch_sync(path)
+    }
+
+    fn read_dir(&self, path: &str) -> io::Result<Vec<String>> {
+        let mut entries: Vec<String> = self
+            .resources
+            .keys()
+            .filter_map(|key| key.strip_prefix(path))
+            .filter_map(|rest| rest.split('/').next())
+            .map(String::from)
+            .collect();
+        entries.sort();
+        entries.dedup();
+        Ok(entries)
+    }
+}
+
+impl ResourceManager<EmbeddedResources> {
+    /// Creates a manager generating bundles out of [`EmbeddedResources`].
+    pub fn embedded(resources: EmbeddedResources) -> Self {
+        Self::with_fetcher(PATH_SCHEME.to_string(), resources)
+    }
+}
@@ -0,0 +1,45 @@
+use fluent_bundle::FluentError;
+use fluent_fallback::{generator::GeneratorError, LocalizationError};
+use std::error::Error;
+use std::io;
+use unic_langid::LanguageIdentifier;
+
+/// An error reported along with a bundle generated by this crate.
+#[derive(Debug, PartialEq, Clone)]
+pub enum BundleError {
+    /// An error of parsing the resource `id`, or of adding it to the bundle.
+    Fluent { id: String, error: FluentError },
+    /// The resource `id` could not be loaded, for example because its file
+    /// is missing or could not be read.
+    ResourceLoad { id: String, kind: io::ErrorKind },
+}
+
+impl std::fmt::Display for BundleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fluent { id, error } => write!(f, "{}: {}", id, error),
+            Self::ResourceLoad { id, kind } => {
+                write!(f, "Failed to load resource \"{}\": {}.", id, kind)
+            }
+        }
+    }
+}
+
+impl Error for BundleError {}
+
+impl GeneratorError for BundleError {
+    fn to_localization_error(&self, locale: &LanguageIdentifier) -> LocalizationError {
+        match self {
+            Self::Fluent { id, error } => LocalizationError::Bundle {
+                locale: locale.clone(),
+                res_id: Some(id.clone()),
+                error: error.clone(),
+            },
+            Self::ResourceLoad { id, kind } => LocalizationError::ResourceLoad {
+                id: id.clone(),
+                locale: locale.clone(),
+                kind: *kind,
+            },
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Pluggable access to the storage holding localization resources.
+use async_trait::async_trait;
+use futures::channel::oneshot;
+use std::fs;
+use std::io;
+use std::sync::{mpsc, OnceLock};
+use std::thread;
+use std::time::SystemTime;
+
+/// Reads the source of resource files for a [`ResourceManager`](crate::ResourceManager).
+///
+/// The default implementation, [`FsFileFetcher`], reads files from the
+/// file system, but any other storage can be plugged in, which is
+/// particularly useful for testing.
+///
+/// # Example
+///
+/// ```
+/// use async_trait::async_trait;
+/// use fluent_resmgr::FileFetcher;
+/// use std::collections::HashMap;
+/// use std::io;
+///
+/// struct MemoryFetcher(HashMap<String, String>);
+///
+/// #[async_trait(?Send)]
+/// impl FileFetcher for MemoryFetcher {
+///     fn fetch_sync(&self, path: &str) -> io::Result<String> {
+///         self.0
+///             .get(path)
+///             .cloned()
+///             .ok_or_else(|| io::ErrorKind::NotFound.into())
+///     }
+///
+///     async fn fetch(&self, path: &str) -> io::Result<String> {
+///         self.fetch_sync(path)
+///     }
+/// }
+/// ```
+#[async_trait(?Send)]
+pub trait FileFetcher {
+    /// Reads the file at `path`, blocking until it's read.
+    fn fetch_sync(&self, path: &str) -> io::Result<String>;
+
+    /// Reads the file at `path` without blocking the executor.
+    async fn fetch(&self, path: &str) -> io::Result<String>;
+
+    /// Returns the last modification time of the file at `path`, which is
+    /// used to detect changed files when reloading resources.
+    ///
+    /// The default implementation returns an [`io::ErrorKind::Unsupported`]
+    /// error, in which case files are assumed to never change.
+    fn modified(&self, _path: &str) -> io::Result<SystemTime> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    /// Returns the names of the entries of the directory at `path`, which is
+    /// used to discover the available locales. An empty `path` stands for
+    /// the current directory.
+    ///
+    /// The default implementation returns an [`io::ErrorKind::Unsupported`] error.
+    fn read_dir(&self, _path: &str) -> io::Result<Vec<String>> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+/// A [`FileFetcher`] reading files from the file system.
+///
+/// Asynchronous reads are queued to a single background thread shared by
+/// all fetchers, so they never block the executor polling them, whichever
+/// runtime it belongs to. Applications which prefer to read files on their
+/// runtime's blocking pool, such as `tokio::task::spawn_blocking`, can
+/// implement [`FileFetcher`] on top of it instead.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FsFileFetcher;
+
+#[async_trait(?Send)]
+impl FileFetcher for FsFileFetcher {
+    fn fetch_sync(&self, path: &str) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+
+    async fn fetch(&self, path: &str) -> io::Result<String> {
+        let (sender, receiver) = oneshot::channel();
+        reader()
+            .send((path.to_string(), sender))
+            .map_err(|_| io::Error::other("file reading thread stopped"))?;
+        receiver
+            .await
+            .unwrap_or_else(|_| Err(io::Error::other("file reading thread stopped")))
+    }
+
+    fn modified(&self, path: &str) -> io::Result<SystemTime> {
+        fs::metadata(path)?.modified()
+    }
+
+    fn read_dir(&self, path: &str) -> io::Result<Vec<String>> {
+        let path = if path.is_empty() { "." } else { path };
+        fs::read_dir(path)?
+            .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+            .collect()
+    }
+}
+
+/// A file to read on the reader thread, along with the sender of the result.
+type ReadRequest = (String, oneshot::Sender<io::Result<String>>);
+
+/// Returns the queue of the thread reading files for [`FsFileFetcher::fetch`],
+/// spawning the thread on first use.
+fn reader() -> &'static mpsc::Sender<ReadRequest> {
+    static READER: OnceLock<mpsc::Sender<ReadRequest>> = OnceLock::new();
+    READER.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<ReadRequest>();
+        thread::Builder::new()
+            .name("fluent-resmgr-reader".to_string())
+            .spawn(move || {
+                for (path, sender) in receiver {
+                    // The receiver may have been dropped if the read is no longer needed.
+                    let _ = sender.send(fs::read_to_string(path));
+                }
+            })
+            .expect("Failed to spawn the file reading thread.");
+        sender
+    })
+}
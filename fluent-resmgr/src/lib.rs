@@ -1,3 +1,13 @@
+pub mod embedded;
+mod errors;
+pub mod fetcher;
+mod loader;
+pub mod registry;
 pub mod resource_manager;
+pub mod solver;
 
+pub use embedded::EmbeddedResources;
+pub use errors::BundleError;
+pub use fetcher::{FileFetcher, FsFileFetcher};
+pub use registry::{FileSource, ResourceRegistry};
 pub use resource_manager::ResourceManager;
@@ -0,0 +1,217 @@
+//! Resource loading and bundle building shared by the generators of this crate.
+
+// `FluentBundleResult` is dictated by `BundleGenerator`.
+#![allow(clippy::result_large_err)]
+
+use crate::errors::BundleError;
+use crate::fetcher::FileFetcher;
+use fluent_bundle::{FluentBundle, FluentError, FluentResource};
+use fluent_fallback::{generator::FluentBundleResult, types::ResourceId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io;
+use std::rc::Rc;
+use std::time::SystemTime;
+use unic_langid::LanguageIdentifier;
+
+/// A loaded resource, along with the errors encountered while parsing it.
+///
+/// The parse errors are cached along with the resource, so they're
+/// reported by every bundle using it.
+pub(crate) type LoadedResource = io::Result<(Rc<FluentResource>, Vec<FluentError>)>;
+
+/// Resolves a path scheme for a given locale and resource id.
+///
+/// The `{locale}` placeholder is replaced with the locale, and the
+/// `{res_id}` placeholder with the resource id.
+pub(crate) fn get_path(path_scheme: &str, locale: &str, res_id: &str) -> String {
+    path_scheme
+        .replace("{locale}", locale)
+        .replace("{res_id}", res_id)
+}
+
+/// A parsed resource and its parse errors, or the kind of error which
+/// prevented fetching it, along with the modification time of its file.
+struct CachedResource {
+    result: Result<(Rc<FluentResource>, Vec<FluentError>), io::ErrorKind>,
+    modified: Option<SystemTime>,
+}
+
+impl CachedResource {
+    fn get(&self) -> LoadedResource {
+        self.result.clone().map_err(io::Error::from)
+    }
+}
+
+/// Lists the locales for which the directory holding the `{locale}`
+/// segment of a path scheme has an entry.
+///
+/// The segment may have a prefix and a suffix, as in `"main-{locale}.ftl"`.
+/// Entries which aren't valid language identifiers are skipped, and the
+/// locales are sorted for a stable order.
+pub(crate) fn list_locales<F: FileFetcher>(
+    fetcher: &F,
+    path_scheme: &str,
+) -> io::Result<Vec<LanguageIdentifier>> {
+    let idx = path_scheme.find("{locale}").ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "path scheme has no {locale} placeholder",
+        )
+    })?;
+    let head = &path_scheme[..idx];
+    let (dir, prefix) = head.split_at(head.rfind('/').map_or(0, |i| i + 1));
+    let tail = &path_scheme[idx + "{locale}".len()..];
+    let suffix = tail.split('/').next().unwrap_or_default();
+
+    let mut locales: Vec<LanguageIdentifier> = fetcher
+        .read_dir(dir)?
+        .iter()
+        .filter_map(|name| name.strip_prefix(prefix)?.strip_suffix(suffix))
+        .filter_map(|locale| locale.parse().ok())
+        .collect();
+    locales.sort_by_cached_key(|locale| locale.to_string());
+    locales.dedup();
+    Ok(locales)
+}
+
+/// Fetches resources and caches them by path once parsed.
+pub(crate) struct ResourceLoader<F> {
+    resources: RefCell<HashMap<String, CachedResource>>,
+    pub fetcher: F,
+}
+
+impl<F: FileFetcher> ResourceLoader<F> {
+    pub fn new(fetcher: F) -> Self {
+        Self {
+            resources: RefCell::new(HashMap::new()),
+            fetcher,
+        }
+    }
+
+    fn get_cached(&self, path: &str) -> Option<LoadedResource> {
+        self.resources.borrow().get(path).map(CachedResource::get)
+    }
+
+    fn cache_resource(
+        &self,
+        path: &str,
+        source: io::Result<String>,
+        modified: Option<SystemTime>,
+    ) -> LoadedResource {
+        let result = source
+            .map(|source| {
+                let (res, errors) = match FluentResource::try_new(source) {
+                    Ok(res) => (res, vec![]),
+                    Err((res, errors)) => (res, errors.into_iter().map(Into::into).collect()),
+                };
+                (Rc::new(res), errors)
+            })
+            .map_err(|err| err.kind());
+        let cached = CachedResource { result, modified };
+        let loaded = cached.get();
+        self.resources.borrow_mut().insert(path.to_string(), cached);
+        loaded
+    }
+
+    /// Loads the resource at `path`, from the cache if it was loaded before.
+    ///
+    /// Failed fetches are cached too, so a missing file isn't fetched again
+    /// until [`ResourceLoader::reload`] notices it appeared.
+    pub fn load_sync(&self, path: &str) -> LoadedResource {
+        if let Some(loaded) = self.get_cached(path) {
+            return loaded;
+        }
+        // Read the time first, so that changes made while reading are detected.
+        let modified = self.fetcher.modified(path).ok();
+        let source = self.fetcher.fetch_sync(path);
+        self.cache_resource(path, source, modified)
+    }
+
+    pub async fn load(&self, path: &str) -> LoadedResource {
+        if let Some(loaded) = self.get_cached(path) {
+            return loaded;
+        }
+        let modified = self.fetcher.modified(path).ok();
+        let source = self.fetcher.fetch(path).await;
+        self.cache_resource(path, source, modified)
+    }
+
+    /// Drops the cached resources whose files have been modified, removed
+    /// or created since they were loaded, so they're fetched and parsed
+    /// again on the next load. Returns `true` if any resource was dropped.
+    ///
+    /// Resources are kept if the fetcher doesn't support modification times.
+    pub fn reload(&self) -> bool {
+        let mut resources = self.resources.borrow_mut();
+        let count = resources.len();
+        resources.retain(|path, cached| self.fetcher.modified(path).ok() == cached.modified);
+        resources.len() != count
+    }
+}
+
+/// A function adapting newly created bundles, for example by adding functions.
+pub(crate) type AdaptBundle = fn(&mut FluentBundle<Rc<FluentResource>>);
+
+/// Builds a bundle out of loaded resources, or returns `None` if
+/// any of the required resources could not be loaded.
+pub(crate) fn build_bundle(
+    locale: LanguageIdentifier,
+    res_ids: &[ResourceId],
+    resources: Vec<LoadedResource>,
+    adapt: Option<AdaptBundle>,
+) -> Option<FluentBundleResult<Rc<FluentResource>, BundleError>> {
+    if res_ids
+        .iter()
+        .zip(&resources)
+        .any(|(res_id, resource)| res_id.is_required() && resource.is_err())
+    {
+        return None;
+    }
+
+    let mut bundle = FluentBundle::new(vec![locale]);
+    if let Some(adapt) = adapt {
+        adapt(&mut bundle);
+    }
+    let res_ids = res_ids.iter().map(|res_id| res_id.value.as_str());
+    let errors = add_resources(&mut bundle, res_ids.zip(resources));
+    Some(into_bundle_result(bundle, errors))
+}
+
+/// Adds loaded resources to a bundle, returning the errors encountered
+/// while loading, parsing or adding them.
+pub(crate) fn add_resources<'r>(
+    bundle: &mut FluentBundle<Rc<FluentResource>>,
+    resources: impl Iterator<Item = (&'r str, LoadedResource)>,
+) -> Vec<BundleError> {
+    let mut errors = vec![];
+    for (res_id, resource) in resources {
+        match resource {
+            Ok((res, parse_errors)) => {
+                let add_errors = bundle.add_resource(res).err().unwrap_or_default();
+                errors.extend(parse_errors.into_iter().chain(add_errors).map(|error| {
+                    BundleError::Fluent {
+                        id: res_id.to_string(),
+                        error,
+                    }
+                }));
+            }
+            Err(err) => errors.push(BundleError::ResourceLoad {
+                id: res_id.to_string(),
+                kind: err.kind(),
+            }),
+        }
+    }
+    errors
+}
+
+pub(crate) fn into_bundle_result(
+    bundle: FluentBundle<Rc<FluentResource>>,
+    errors: Vec<BundleError>,
+) -> FluentBundleResult<Rc<FluentResource>, BundleError> {
+    if errors.is_empty() {
+        Ok(bundle)
+    } else {
+        Err((bundle, errors))
+    }
+}
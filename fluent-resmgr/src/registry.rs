@@ -0,0 +1,408 @@
+// `FluentBundleResult` is dictated by `BundleGenerator`.
+#![allow(clippy::result_large_err)]
+
+use crate::errors::BundleError;
+use crate::fetcher::{FileFetcher, FsFileFetcher};
+use crate::loader::{self, build_bundle, AdaptBundle, LoadedResource, ResourceLoader};
+use crate::resource_manager::BundleStream;
+use crate::solver::{ProblemSolver, Solution};
+use fluent_bundle::{FluentBundle, FluentError, FluentResource};
+use fluent_fallback::{
+    generator::{self, BundleGenerator, FluentBundleResult},
+    types::ResourceId,
+};
+use futures::stream::{self, StreamExt};
+use std::collections::HashMap;
+use std::io;
+use std::rc::Rc;
+use unic_langid::LanguageIdentifier;
+
+/// A named location of localization resources, available in a set of locales.
+///
+/// The path scheme follows the same rules as the one of
+/// [`ResourceManager`](crate::ResourceManager), for example
+/// `"./plugins/{locale}/{res_id}"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSource {
+    pub name: String,
+    pub locales: Vec<LanguageIdentifier>,
+    pub path_scheme: String,
+}
+
+impl FileSource {
+    pub fn new(name: String, path_scheme: String, locales: Vec<LanguageIdentifier>) -> Self {
+        Self {
+            name,
+            locales,
+            path_scheme,
+        }
+    }
+
+    /// Returns `true` if the source provides resources for the given locale.
+    pub fn has_locale(&self, locale: &LanguageIdentifier) -> bool {
+        self.locales.contains(locale)
+    }
+
+    fn get_path(&self, locale: &str, res_id: &str) -> String {
+        loader::get_path(&self.path_scheme, locale, res_id)
+    }
+}
+
+/// A snapshot of the registered sources, along with the loader used to read
+/// them, shared by the bundle iterators and streams of a registry.
+struct SourceSet<F> {
+    loader: Rc<ResourceLoader<F>>,
+    sources: Vec<FileSource>,
+    adapt_bundle: Option<AdaptBundle>,
+}
+
+/// The bundles of a single locale, enumerated by a [`ProblemSolver`].
+struct LocaleProblem {
+    locale: LanguageIdentifier,
+    locale_str: String,
+    /// Indices of the sources providing the locale, in priority order.
+    rows: Vec<usize>,
+    solver: ProblemSolver,
+    loaded: HashMap<(usize, usize), (Rc<FluentResource>, Vec<FluentError>)>,
+}
+
+impl LocaleProblem {
+    fn new<F>(sources: &SourceSet<F>, locale: LanguageIdentifier, res_ids: &[ResourceId]) -> Self {
+        let rows: Vec<_> = sources
+            .sources
+            .iter()
+            .enumerate()
+            .filter(|(_, source)| source.has_locale(&locale))
+            .map(|(idx, _)| idx)
+            .collect();
+        let optional = res_ids.iter().map(ResourceId::is_optional).collect();
+        Self {
+            locale_str: locale.to_string(),
+            locale,
+            solver: ProblemSolver::new(rows.len(), optional),
+            rows,
+            loaded: HashMap::new(),
+        }
+    }
+
+    fn get_path<F>(
+        &self,
+        sources: &SourceSet<F>,
+        res_ids: &[ResourceId],
+        cell: (usize, usize),
+    ) -> String {
+        let source = &sources.sources[self.rows[cell.1]];
+        source.get_path(&self.locale_str, &res_ids[cell.0].value)
+    }
+
+    fn record(&mut self, cell: (usize, usize), resource: LoadedResource) {
+        let available = resource.is_ok();
+        if let Ok(resource) = resource {
+            self.loaded.insert(cell, resource);
+        }
+        self.solver.set_cell(cell.0, cell.1, available);
+    }
+
+    fn next_sync<F: FileFetcher>(
+        &mut self,
+        sources: &SourceSet<F>,
+        res_ids: &[ResourceId],
+    ) -> Option<FluentBundleResult<Rc<FluentResource>, BundleError>> {
+        loop {
+            match self.solver.try_next() {
+                Ok(solution) => return self.build(sources, res_ids, solution?),
+                Err(cell) => {
+                    let path = self.get_path(sources, res_ids, cell);
+                    self.record(cell, sources.loader.load_sync(&path));
+                }
+            }
+        }
+    }
+
+    async fn next<F: FileFetcher>(
+        &mut self,
+        sources: &SourceSet<F>,
+        res_ids: &[ResourceId],
+    ) -> Option<FluentBundleResult<Rc<FluentResource>, BundleError>> {
+        loop {
+            match self.solver.try_next() {
+                Ok(solution) => return self.build(sources, res_ids, solution?),
+                Err(cell) => {
+                    let path = self.get_path(sources, res_ids, cell);
+                    self.record(cell, sources.loader.load(&path).await);
+                }
+            }
+        }
+    }
+
+    fn build<F>(
+        &mut self,
+        sources: &SourceSet<F>,
+        res_ids: &[ResourceId],
+        solution: Solution,
+    ) -> Option<FluentBundleResult<Rc<FluentResource>, BundleError>> {
+        let resources = solution
+            .into_iter()
+            .enumerate()
+            .map(|(res_idx, row)| {
+                let (res, errors) = row
+                    .and_then(|row| self.loaded.get_mut(&(res_idx, row)))
+                    .ok_or(io::ErrorKind::NotFound)?;
+                // Parse errors are only reported by the first bundle using the resource.
+                Ok((res.clone(), std::mem::take(errors)))
+            })
+            .collect();
+        build_bundle(
+            self.locale.clone(),
+            res_ids,
+            resources,
+            sources.adapt_bundle,
+        )
+    }
+}
+
+/// The state of bundle generation shared by sync and async generators.
+struct RegistryBundles<F> {
+    sources: SourceSet<F>,
+    locales: <Vec<LanguageIdentifier> as IntoIterator>::IntoIter,
+    res_ids: Vec<ResourceId>,
+    problem: Option<LocaleProblem>,
+}
+
+impl<F: FileFetcher> RegistryBundles<F> {
+    fn next_sync(&mut self) -> Option<FluentBundleResult<Rc<FluentResource>, BundleError>> {
+        loop {
+            let problem = match &mut self.problem {
+                Some(problem) => problem,
+                None => {
+                    let locale = self.locales.next()?;
+                    self.problem
+                        .insert(LocaleProblem::new(&self.sources, locale, &self.res_ids))
+                }
+            };
+            if let Some(bundle) = problem.next_sync(&self.sources, &self.res_ids) {
+                return Some(bundle);
+            }
+            self.problem = None;
+        }
+    }
+
+    async fn next(&mut self) -> Option<FluentBundleResult<Rc<FluentResource>, BundleError>> {
+        loop {
+            let problem = match &mut self.problem {
+                Some(problem) => problem,
+                None => {
+                    let locale = self.locales.next()?;
+                    self.problem
+                        .insert(LocaleProblem::new(&self.sources, locale, &self.res_ids))
+                }
+            };
+            if let Some(bundle) = problem.next(&self.sources, &self.res_ids).await {
+                return Some(bundle);
+            }
+            self.problem = None;
+        }
+    }
+}
+
+/// Generates bundles out of resources spread over multiple [`FileSource`]s.
+///
+/// Sources are searched in the order they were registered in, so the
+/// first registered source has the highest priority. The first bundle
+/// generated for a locale takes every resource from the first source which
+/// provides the locale and has the resource file.
+///
+/// When more than one source has some of the resources, further bundles
+/// are generated for the same locale out of the alternative combinations of
+/// sources, in preference order, before moving on to the next locale. This
+/// way a message missing from a higher priority version of a resource falls
+/// back to a lower priority version of it first. Combinations are enumerated
+/// lazily with a [`ProblemSolver`](crate::solver::ProblemSolver), so resource
+/// files are only read once a bundle needs them.
+///
+/// A locale is skipped if any of its required resources can't be found
+/// in any of the sources, while missing optional resources are reported
+/// as errors of the bundle, just like in [`ResourceManager`](crate::ResourceManager).
+///
+/// # Example
+///
+/// ```
+/// use fluent_fallback::{generator::BundleGenerator, types::ResourceId};
+/// use fluent_resmgr::{FileSource, ResourceRegistry};
+/// use unic_langid::langid;
+///
+/// let mut registry = ResourceRegistry::new();
+/// registry.register_source(FileSource::new(
+///     "overrides".to_string(),
+///     "./tests/resources/overrides/{locale}/{res_id}".to_string(),
+///     vec![langid!("pl")],
+/// ));
+/// registry.register_source(FileSource::new(
+///     "app".to_string(),
+///     "./tests/resources/{locale}/{res_id}".to_string(),
+///     vec![langid!("en-US"), langid!("pl")],
+/// ));
+///
+/// assert_eq!(registry.locales(), vec![langid!("pl"), langid!("en-US")]);
+///
+/// let mut bundles = registry.bundles_iter(
+///     vec![langid!("pl")].into_iter(),
+///     vec![ResourceId::from("test.ftl")],
+/// );
+/// let bundle = bundles.next().unwrap().ok().unwrap();
+/// assert!(bundle.has_message("hello-world"));
+/// ```
+pub struct ResourceRegistry<F = FsFileFetcher> {
+    loader: Rc<ResourceLoader<F>>,
+    sources: Vec<FileSource>,
+    adapt_bundle: Option<AdaptBundle>,
+}
+
+impl ResourceRegistry {
+    pub fn new() -> Self {
+        Self::with_fetcher(FsFileFetcher)
+    }
+}
+
+impl Default for ResourceRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: FileFetcher> ResourceRegistry<F> {
+    /// Creates a registry which reads resource files using a custom [`FileFetcher`].
+    pub fn with_fetcher(fetcher: F) -> Self {
+        Self {
+            loader: Rc::new(ResourceLoader::new(fetcher)),
+            sources: vec![],
+            adapt_bundle: None,
+        }
+    }
+
+    /// Registers a source with a lower priority than all of the sources
+    /// registered so far, replacing any existing source with the same name.
+    ///
+    /// Bundle iterators and streams which have already been created
+    /// keep using the sources they were created with.
+    pub fn register_source(&mut self, source: FileSource) {
+        self.sources.retain(|s| s.name != source.name);
+        self.sources.push(source);
+    }
+
+    /// Removes the source with the given name, returning it if it existed.
+    pub fn remove_source(&mut self, name: &str) -> Option<FileSource> {
+        let idx = self.sources.iter().position(|s| s.name == name)?;
+        Some(self.sources.remove(idx))
+    }
+
+    /// Returns the source with the given name.
+    pub fn source(&self, name: &str) -> Option<&FileSource> {
+        self.sources.iter().find(|s| s.name == name)
+    }
+
+    /// Returns the registered sources, in priority order.
+    pub fn sources(&self) -> &[FileSource] {
+        &self.sources
+    }
+
+    /// Sets a function called with every newly created bundle before
+    /// resources are added to it, for example to add custom functions or
+    /// change the bundle settings.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fluent_bundle::FluentValue;
+    /// use fluent_resmgr::ResourceRegistry;
+    ///
+    /// let mut registry = ResourceRegistry::new();
+    /// registry.set_adapt_bundle(Some(|bundle| {
+    ///     bundle.set_use_isolating(false);
+    ///     bundle
+    ///         .add_function("PLATFORM", |_, _| FluentValue::from("linux"))
+    ///         .expect("Failed to add a function.");
+    /// }));
+    /// ```
+    pub fn set_adapt_bundle(&mut self, adapt: Option<fn(&mut FluentBundle<Rc<FluentResource>>)>) {
+        self.adapt_bundle = adapt;
+    }
+
+    /// Drops cached resources whose files have changed since they were
+    /// loaded, returning `true` if any of them changed.
+    ///
+    /// See [`ResourceManager::reload`](crate::ResourceManager::reload).
+    pub fn reload(&self) -> bool {
+        self.loader.reload()
+    }
+
+    /// Returns all locales provided by at least one of the sources,
+    /// in the order they are first listed by the sources.
+    pub fn locales(&self) -> Vec<LanguageIdentifier> {
+        let mut locales: Vec<LanguageIdentifier> = vec![];
+        for locale in self.sources.iter().flat_map(|s| &s.locales) {
+            if !locales.contains(locale) {
+                locales.push(locale.clone());
+            }
+        }
+        locales
+    }
+
+    fn bundles(
+        &self,
+        locales: <Vec<LanguageIdentifier> as IntoIterator>::IntoIter,
+        res_ids: Vec<ResourceId>,
+    ) -> RegistryBundles<F> {
+        RegistryBundles {
+            sources: SourceSet {
+                loader: self.loader.clone(),
+                sources: self.sources.clone(),
+                adapt_bundle: self.adapt_bundle,
+            },
+            locales,
+            res_ids,
+            problem: None,
+        }
+    }
+}
+
+/// An iterator over bundles generated by a [`ResourceRegistry`].
+pub struct RegistryBundleIter<F = FsFileFetcher> {
+    bundles: RegistryBundles<F>,
+}
+
+impl<F: FileFetcher> Iterator for RegistryBundleIter<F> {
+    type Item = FluentBundleResult<Rc<FluentResource>, BundleError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.bundles.next_sync()
+    }
+}
+
+impl<F: FileFetcher> generator::BundleIterator for RegistryBundleIter<F> {}
+
+impl<F: FileFetcher + 'static> BundleGenerator for ResourceRegistry<F> {
+    type Resource = Rc<FluentResource>;
+    type Error = BundleError;
+    type LocalesIter = std::vec::IntoIter<LanguageIdentifier>;
+    type Iter = RegistryBundleIter<F>;
+    type Stream = BundleStream;
+
+    fn bundles_iter(&self, locales: Self::LocalesIter, res_ids: Vec<ResourceId>) -> Self::Iter {
+        RegistryBundleIter {
+            bundles: self.bundles(locales, res_ids),
+        }
+    }
+
+    fn bundles_stream(&self, locales: Self::LocalesIter, res_ids: Vec<ResourceId>) -> Self::Stream {
+        let stream = stream::unfold(self.bundles(locales, res_ids), |mut bundles| async move {
+            let bundle = bundles.next().await?;
+            Some((bundle, bundles))
+        });
+        BundleStream::new(stream.boxed_local())
+    }
+
+    fn reload(&self) -> bool {
+        ResourceRegistry::reload(self)
+    }
+}
@@ -1,135 +1,365 @@
-use elsa::FrozenMap;
+// `FluentBundleResult` is dictated by `BundleGenerator`.
+#![allow(clippy::result_large_err)]
+
+use crate::errors::BundleError;
+use crate::fetcher::{FileFetcher, FsFileFetcher};
+use crate::loader::{self, build_bundle, LoadedResource, ResourceLoader};
 use fluent_bundle::{FluentBundle, FluentResource};
 use fluent_fallback::{
-    generator::{BundleGenerator, FluentBundleResult},
+    generator::{self, BundleGenerator, FluentBundleResult},
     types::ResourceId,
 };
-use futures::stream::Stream;
-use std::fs;
+use futures::future::{self, FutureExt, LocalBoxFuture};
+use futures::ready;
+use futures::stream::{self, FuturesOrdered, LocalBoxStream, Stream, StreamExt};
+use std::collections::VecDeque;
 use std::io;
-use std::iter;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
 use unic_langid::LanguageIdentifier;
 
-fn read_file(path: &str) -> Result<String, io::Error> {
-    fs::read_to_string(path)
+struct Inner<F> {
+    loader: ResourceLoader<F>,
+    path_scheme: String,
 }
 
-pub struct ResourceManager {
-    resources: FrozenMap<String, Box<FluentResource>>,
-    path_scheme: String,
+impl<F: FileFetcher> Inner<F> {
+    fn get_path(&self, res_id: &str, locale: &str) -> String {
+        loader::get_path(&self.path_scheme, locale, res_id)
+    }
+
+    fn load_bundle_sync(
+        &self,
+        locale: LanguageIdentifier,
+        res_ids: &[ResourceId],
+    ) -> Option<FluentBundleResult<Rc<FluentResource>, BundleError>> {
+        let locale_str = locale.to_string();
+        let resources = res_ids
+            .iter()
+            .map(|res_id| {
+                self.loader
+                    .load_sync(&self.get_path(&res_id.value, &locale_str))
+            })
+            .collect();
+        build_bundle(locale, res_ids, resources, None)
+    }
+
+    async fn load_bundle(
+        &self,
+        locale: LanguageIdentifier,
+        res_ids: &[ResourceId],
+    ) -> Option<FluentBundleResult<Rc<FluentResource>, BundleError>> {
+        let locale_str = locale.to_string();
+        let paths: Vec<_> = res_ids
+            .iter()
+            .map(|res_id| self.get_path(&res_id.value, &locale_str))
+            .collect();
+        let resources = future::join_all(paths.iter().map(|path| self.loader.load(path))).await;
+        build_bundle(locale, res_ids, resources, None)
+    }
+}
+
+/// Loads resources according to a path scheme and generates bundles from them.
+///
+/// The path scheme is a path containing `{locale}` and `{res_id}`
+/// placeholders, for example `"./resources/{locale}/{res_id}"`.
+/// Resource files are read using a [`FileFetcher`], which defaults to
+/// [`FsFileFetcher`], and cached once parsed.
+///
+/// When generating bundles, a locale is skipped if any of its
+/// [required](fluent_fallback::types::ResourceType::Required) resources
+/// could not be loaded. Missing [optional](fluent_fallback::types::ResourceType::Optional)
+/// resources are reported as [`BundleError::ResourceLoad`] errors
+/// of an otherwise valid bundle.
+pub struct ResourceManager<F = FsFileFetcher> {
+    inner: Rc<Inner<F>>,
+    prefetch_parallelism: usize,
 }
 
 impl ResourceManager {
     pub fn new(path_scheme: String) -> Self {
+        Self::with_fetcher(path_scheme, FsFileFetcher)
+    }
+}
+
+impl<F: FileFetcher> ResourceManager<F> {
+    /// Creates a manager which reads resource files using a custom [`FileFetcher`].
+    pub fn with_fetcher(path_scheme: String, fetcher: F) -> Self {
         ResourceManager {
-            resources: FrozenMap::new(),
-            path_scheme,
+            inner: Rc::new(Inner {
+                loader: ResourceLoader::new(fetcher),
+                path_scheme,
+            }),
+            prefetch_parallelism: 1,
         }
     }
 
-    fn get_resource(&self, res_id: &str, locale: &str) -> &FluentResource {
-        let path = self
-            .path_scheme
-            .replace("{locale}", locale)
-            .replace("{res_id}", res_id);
-        if let Some(res) = self.resources.get(&path) {
-            res
-        } else {
-            let string = read_file(&path).unwrap();
-            let res = match FluentResource::try_new(string) {
-                Ok(res) => res,
-                Err((res, _err)) => res,
-            };
-            self.resources.insert(path.to_string(), Box::new(res))
-        }
+    /// Sets how many locales have their resources loaded concurrently when
+    /// a [`BundleStream`] is prefetched, which defaults to one at a time.
+    ///
+    /// Prefetched bundles are still yielded in the order of the locales.
+    pub fn set_prefetch_parallelism(&mut self, parallelism: usize) {
+        self.prefetch_parallelism = parallelism.max(1);
     }
 
+    fn get_resource(&self, res_id: &str, locale: &str) -> LoadedResource {
+        let path = self.inner.get_path(res_id, locale);
+        self.inner.loader.load_sync(&path)
+    }
+
+    fn load_resources(
+        &self,
+        bundle: &mut FluentBundle<Rc<FluentResource>>,
+        locale: &LanguageIdentifier,
+        resource_ids: &[String],
+    ) -> Vec<BundleError> {
+        let locale = locale.to_string();
+        let resources = resource_ids
+            .iter()
+            .map(|res_id| (res_id.as_str(), self.get_resource(res_id, &locale)));
+        loader::add_resources(bundle, resources)
+    }
+
+    /// Returns a bundle for `locales` with the resources of the first locale.
+    ///
+    /// Resources which could not be loaded, parsed or added to the bundle
+    /// are reported as errors along with the bundle holding the rest of them.
     pub fn get_bundle(
         &self,
         locales: Vec<LanguageIdentifier>,
         resource_ids: Vec<String>,
-    ) -> FluentBundle<&FluentResource> {
+    ) -> FluentBundleResult<Rc<FluentResource>, BundleError> {
         let mut bundle = FluentBundle::new(locales.clone());
-        for res_id in &resource_ids {
-            let res = self.get_resource(res_id, &locales[0].to_string());
-            bundle.add_resource(res).unwrap();
-        }
-        bundle
+        let errors = self.load_resources(&mut bundle, &locales[0], &resource_ids);
+        loader::into_bundle_result(bundle, errors)
     }
 
+    /// Returns a bundle for each of the `locales`, reporting errors
+    /// like [`ResourceManager::get_bundle`].
     pub fn get_bundles(
         &self,
         locales: Vec<LanguageIdentifier>,
         resource_ids: Vec<String>,
-    ) -> impl Iterator<Item = FluentBundle<&FluentResource>> {
-        let res_mgr = self;
-        let mut ptr = 0;
-
-        iter::from_fn(move || {
-            locales.get(ptr).map(|locale| {
-                ptr += 1;
-                let mut bundle = FluentBundle::new(vec![locale.clone()]);
-                for res_id in &resource_ids {
-                    let res = res_mgr.get_resource(&res_id, &locale.to_string());
-                    bundle.add_resource(res).unwrap();
-                }
-                bundle
-            })
+    ) -> impl Iterator<Item = FluentBundleResult<Rc<FluentResource>, BundleError>> + '_ {
+        locales.into_iter().map(move |locale| {
+            let mut bundle = FluentBundle::new(vec![locale.clone()]);
+            let errors = self.load_resources(&mut bundle, &locale, &resource_ids);
+            loader::into_bundle_result(bundle, errors)
         })
     }
+
+    /// Returns the locales available on disk, or in the storage of the
+    /// [`FileFetcher`], found by listing the directory holding the
+    /// `{locale}` segment of the path scheme.
+    ///
+    /// Entries which aren't valid language identifiers are skipped, and the
+    /// locales are sorted by their string representation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fluent_resmgr::ResourceManager;
+    /// use unic_langid::langid;
+    ///
+    /// let res_mgr = ResourceManager::new("./tests/resources/{locale}/{res_id}".into());
+    /// let locales = res_mgr.locales().expect("Failed to list locales.");
+    /// assert_eq!(locales, vec![langid!("en-US"), langid!("pl")]);
+    /// ```
+    pub fn locales(&self) -> io::Result<Vec<LanguageIdentifier>> {
+        loader::list_locales(&self.inner.loader.fetcher, &self.inner.path_scheme)
+    }
+
+    /// Returns the available locales, like [`ResourceManager::locales`],
+    /// which have all of the required resources of `res_ids`.
+    ///
+    /// The resources are loaded to check their availability, so they're
+    /// cached for the bundles generated afterwards.
+    pub fn locales_with(&self, res_ids: &[ResourceId]) -> io::Result<Vec<LanguageIdentifier>> {
+        let mut locales = self.locales()?;
+        locales.retain(|locale| {
+            let locale = locale.to_string();
+            res_ids
+                .iter()
+                .filter(|res_id| res_id.is_required())
+                .all(|res_id| {
+                    let path = self.inner.get_path(&res_id.value, &locale);
+                    self.inner.loader.load_sync(&path).is_ok()
+                })
+        });
+        Ok(locales)
+    }
+
+    /// Drops cached resources whose files have changed since they were
+    /// loaded, including files which were missing and have been created
+    /// since, so that bundles generated afterwards pick up the changes.
+    /// Returns `true` if any of the files changed.
+    ///
+    /// Changes are detected by polling modification times with
+    /// [`FileFetcher::modified`]. Bundles which were already generated
+    /// keep the old resources, so a [`Localization`](fluent_fallback::Localization)
+    /// using the manager should be reloaded with
+    /// [`Localization::reload`](fluent_fallback::Localization::reload), which
+    /// calls this method and notifies the observers of the localization.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fluent_fallback::Localization;
+    /// use fluent_resmgr::ResourceManager;
+    ///
+    /// let res_mgr = ResourceManager::new("./tests/resources/{locale}/{res_id}".into());
+    /// let mut loc = Localization::with_env(
+    ///     vec!["test.ftl".into()],
+    ///     true,
+    ///     vec!["en-US".parse().unwrap()],
+    ///     res_mgr,
+    /// );
+    ///
+    /// // Called periodically, for example on a timer.
+    /// if loc.reload() {
+    ///     // Formatted translations are out of date.
+    /// }
+    /// ```
+    pub fn reload(&self) -> bool {
+        self.inner.loader.reload()
+    }
 }
 
 // Due to limitation of trait, we need a nameable Iterator type.  Due to the
 // lack of GATs, these have to own members instead of taking slices.
-pub struct BundleIter {
+pub struct BundleIter<F = FsFileFetcher> {
+    inner: Rc<Inner<F>>,
     locales: <Vec<LanguageIdentifier> as IntoIterator>::IntoIter,
     res_ids: Vec<ResourceId>,
 }
 
-impl Iterator for BundleIter {
-    type Item = FluentBundleResult<FluentResource>;
+impl<F: FileFetcher> Iterator for BundleIter<F> {
+    type Item = FluentBundleResult<Rc<FluentResource>, BundleError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let locale = self.locales.next()?;
+        let (inner, res_ids) = (&self.inner, &self.res_ids);
+        self.locales
+            .find_map(|locale| inner.load_bundle_sync(locale, res_ids))
+    }
+}
 
-        let mut bundle = FluentBundle::new(vec![locale.clone()]);
+impl<F: FileFetcher> generator::BundleIterator for BundleIter<F> {}
 
-        for res_id in &self.res_ids {
-            let full_path = format!("./tests/resources/{}/{}", locale, res_id);
-            let source = fs::read_to_string(full_path).unwrap();
-            let res = FluentResource::try_new(source).unwrap();
-            bundle.add_resource(res).unwrap();
+/// Loads the bundle of a locale, or resolves to `None` if the locale is skipped.
+type BundleFuture =
+    LocalBoxFuture<'static, Option<FluentBundleResult<Rc<FluentResource>, BundleError>>>;
+
+/// A stream of bundles, one per available locale, generated by a
+/// [`ResourceManager`] or a [`ResourceRegistry`](crate::ResourceRegistry).
+///
+/// All resources of a bundle are loaded concurrently, and the bundle is
+/// yielded once all of them are available. Bundles are loaded one at a time,
+/// unless the stream is prefetched with a
+/// [parallelism](ResourceManager::set_prefetch_parallelism) greater than one.
+pub struct BundleStream {
+    futures: stream::Fuse<LocalBoxStream<'static, BundleFuture>>,
+    pending: FuturesOrdered<BundleFuture>,
+    ready: VecDeque<FluentBundleResult<Rc<FluentResource>, BundleError>>,
+    parallelism: usize,
+}
+
+impl BundleStream {
+    pub(crate) fn new(
+        stream: LocalBoxStream<'static, FluentBundleResult<Rc<FluentResource>, BundleError>>,
+    ) -> Self {
+        let futures = stream.map(|bundle| future::ready(Some(bundle)).boxed_local());
+        Self::with_futures(futures.boxed_local(), 1)
+    }
+
+    pub(crate) fn with_futures(
+        futures: LocalBoxStream<'static, BundleFuture>,
+        parallelism: usize,
+    ) -> Self {
+        // `Localization` may poll the stream again once it has finished.
+        Self {
+            futures: futures.fuse(),
+            pending: FuturesOrdered::new(),
+            ready: VecDeque::new(),
+            parallelism,
         }
-        Some(Ok(bundle))
     }
 }
 
-impl Stream for BundleIter {
-    type Item = FluentBundleResult<FluentResource>;
+impl Stream for BundleStream {
+    type Item = FluentBundleResult<Rc<FluentResource>, BundleError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(bundle) = self.ready.pop_front() {
+            return Poll::Ready(Some(bundle));
+        }
+        loop {
+            if self.pending.is_empty() {
+                match ready!(self.futures.poll_next_unpin(cx)) {
+                    Some(future) => self.pending.push_back(future),
+                    None => return Poll::Ready(None),
+                }
+            }
+            if let Some(Some(bundle)) = ready!(self.pending.poll_next_unpin(cx)) {
+                return Poll::Ready(Some(bundle));
+            }
+        }
+    }
+}
 
-    fn poll_next(
-        self: std::pin::Pin<&mut Self>,
-        _cx: &mut std::task::Context<'_>,
-    ) -> std::task::Poll<Option<Self::Item>> {
-        todo!()
+#[async_trait::async_trait(?Send)]
+impl generator::BundleStream for BundleStream {
+    /// Loads all remaining bundles, up to the parallelism of the
+    /// [`ResourceManager`] at a time.
+    async fn prefetch_async(&mut self) {
+        loop {
+            while self.pending.len() < self.parallelism {
+                match self.futures.next().await {
+                    Some(future) => self.pending.push_back(future),
+                    None => break,
+                }
+            }
+            match self.pending.next().await {
+                Some(Some(bundle)) => self.ready.push_back(bundle),
+                Some(None) => {}
+                None => break,
+            }
+        }
     }
 }
 
-impl BundleGenerator for ResourceManager {
-    type Resource = FluentResource;
+impl<F: FileFetcher + 'static> BundleGenerator for ResourceManager<F> {
+    type Resource = Rc<FluentResource>;
+    type Error = BundleError;
     type LocalesIter = std::vec::IntoIter<LanguageIdentifier>;
-    type Iter = BundleIter;
-    type Stream = BundleIter;
+    type Iter = BundleIter<F>;
+    type Stream = BundleStream;
 
     fn bundles_iter(&self, locales: Self::LocalesIter, res_ids: Vec<ResourceId>) -> Self::Iter {
-        BundleIter { locales, res_ids }
+        BundleIter {
+            inner: self.inner.clone(),
+            locales,
+            res_ids,
+        }
     }
 
-    fn bundles_stream(
-        &self,
-        _locales: Self::LocalesIter,
-        _res_ids: Vec<ResourceId>,
-    ) -> Self::Stream {
-        todo!()
+    fn bundles_stream(&self, locales: Self::LocalesIter, res_ids: Vec<ResourceId>) -> Self::Stream {
+        let inner = self.inner.clone();
+        let res_ids: Rc<[ResourceId]> = res_ids.into();
+        let futures = stream::iter(locales).map(move |locale| {
+            let inner = inner.clone();
+            let res_ids = res_ids.clone();
+            async move { inner.load_bundle(locale, &res_ids).await }.boxed_local()
+        });
+        BundleStream::with_futures(futures.boxed_local(), self.prefetch_parallelism)
+    }
+
+    fn reload(&self) -> bool {
+        ResourceManager::reload(self)
+    }
+
+    fn has_resource(&self, locale: &LanguageIdentifier, res_id: &ResourceId) -> bool {
+        let path = self.inner.get_path(&res_id.value, &locale.to_string());
+        self.inner.loader.load_sync(&path).is_ok()
     }
 }
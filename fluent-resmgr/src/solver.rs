@@ -0,0 +1,188 @@
+//! Lazy enumeration of the bundles which can be assembled out of resources
+//! available in multiple sources.
+//!
+//! For a single locale, the problem can be pictured as a grid with one
+//! column per resource id and one row per source providing the locale.
+//! A cell is available if the source has the resource. A solution picks one
+//! available cell in every column, and every combination of such picks
+//! can be turned into a bundle, so the number of solutions grows quickly
+//! with the number of sources and resources.
+//!
+//! [`ProblemSolver`] enumerates solutions lazily, in preference order,
+//! testing only the cells needed to reach the next solution. It doesn't
+//! test cells itself, which lets the same solver drive both synchronous
+//! and asynchronous I/O.
+//!
+//! # Example
+//!
+//! ```
+//! use fluent_resmgr::solver::ProblemSolver;
+//!
+//! // Two resources, the second one optional, and two sources.
+//! let mut solver = ProblemSolver::new(2, vec![false, true]);
+//! let available = [[true, false], [true, true]];
+//!
+//! let mut test = |res_idx: usize, source_idx: usize| available[source_idx][res_idx];
+//! assert_eq!(solver.next_sync(&mut test), Some(vec![Some(0), Some(1)]));
+//! assert_eq!(solver.next_sync(&mut test), Some(vec![Some(1), Some(1)]));
+//! assert_eq!(solver.next_sync(&mut test), None);
+//! ```
+
+/// A solution of a problem, holding the index of the source picked for every
+/// resource, or `None` for optional resources missing from all sources.
+pub type Solution = Vec<Option<usize>>;
+
+/// Lazily enumerates the solutions of a problem in preference order.
+///
+/// Solutions are ordered lexicographically by the source indices they
+/// pick, where sources with lower indices are preferred and the picks of
+/// earlier resources are more significant. The first solution takes every
+/// resource from the most preferred source which has it.
+///
+/// Optional resources which no source has are left out of the solution,
+/// while a required resource missing from all sources means there is no
+/// solution at all, which is detected as soon as that column is tested.
+#[derive(Debug, Clone)]
+pub struct ProblemSolver {
+    depth: usize,
+    optional: Vec<bool>,
+    cells: Vec<Option<bool>>,
+    picks: Vec<usize>,
+    state: State,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Initial,
+    Solved,
+    Exhausted,
+}
+
+impl ProblemSolver {
+    /// Creates a solver for a problem with `depth` sources and one resource
+    /// for every element of `optional`, which tells if the resource is optional.
+    pub fn new(depth: usize, optional: Vec<bool>) -> Self {
+        Self {
+            depth,
+            cells: vec![None; optional.len() * depth],
+            optional,
+            picks: vec![],
+            state: State::Initial,
+        }
+    }
+
+    /// Returns the number of resources of the problem.
+    pub fn width(&self) -> usize {
+        self.optional.len()
+    }
+
+    /// Returns the number of sources of the problem.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Records whether the source at `source_idx` has the resource at `res_idx`.
+    pub fn set_cell(&mut self, res_idx: usize, source_idx: usize, available: bool) {
+        let idx = self.cell_idx(res_idx, source_idx);
+        self.cells[idx] = Some(available);
+    }
+
+    /// Advances to the next solution.
+    ///
+    /// Returns `Ok(None)` once all solutions have been enumerated, or
+    /// `Err((res_idx, source_idx))` if the cell has to be tested and recorded
+    /// with [`ProblemSolver::set_cell`] first, in which case the solver
+    /// doesn't advance and the call should be repeated.
+    pub fn try_next(&mut self) -> Result<Option<Solution>, (usize, usize)> {
+        let mut picks = self.picks.clone();
+        let result = match self.state {
+            State::Exhausted => return Ok(None),
+            State::Initial => self.descend(&mut picks),
+            State::Solved => match self.advance(&mut picks)? {
+                true => self.descend(&mut picks),
+                false => Ok(false),
+            },
+        };
+
+        // Only commit to the new picks once no untested cell was encountered.
+        let solved = result?;
+        self.picks = picks;
+        if solved {
+            self.state = State::Solved;
+            Ok(Some(self.solution()))
+        } else {
+            self.state = State::Exhausted;
+            Ok(None)
+        }
+    }
+
+    /// Advances to the next solution, testing cells with `test` as needed.
+    pub fn next_sync<T>(&mut self, mut test: T) -> Option<Solution>
+    where
+        T: FnMut(usize, usize) -> bool,
+    {
+        loop {
+            match self.try_next() {
+                Ok(solution) => return solution,
+                Err((res_idx, source_idx)) => {
+                    let available = test(res_idx, source_idx);
+                    self.set_cell(res_idx, source_idx, available);
+                }
+            }
+        }
+    }
+
+    fn cell_idx(&self, res_idx: usize, source_idx: usize) -> usize {
+        res_idx * self.depth + source_idx
+    }
+
+    /// Finds the first available source for a resource, starting at `start`.
+    fn find_source(&self, res_idx: usize, start: usize) -> Result<Option<usize>, (usize, usize)> {
+        for source_idx in start..self.depth {
+            match self.cells[self.cell_idx(res_idx, source_idx)] {
+                Some(true) => return Ok(Some(source_idx)),
+                Some(false) => {}
+                None => return Err((res_idx, source_idx)),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Extends the picks with the first available source of every remaining
+    /// resource. Returns `false` if a required resource has no source.
+    fn descend(&self, picks: &mut Vec<usize>) -> Result<bool, (usize, usize)> {
+        while picks.len() < self.width() {
+            let res_idx = picks.len();
+            match self.find_source(res_idx, 0)? {
+                Some(source_idx) => picks.push(source_idx),
+                // The missing optional resource is represented by `depth`.
+                None if self.optional[res_idx] => picks.push(self.depth),
+                None => return Ok(false),
+            }
+        }
+        Ok(true)
+    }
+
+    /// Moves the least significant pick which has an alternative to its next
+    /// available source, dropping all of the picks after it. Returns `false`
+    /// if there are no alternatives left.
+    fn advance(&self, picks: &mut Vec<usize>) -> Result<bool, (usize, usize)> {
+        while let Some(source_idx) = picks.pop() {
+            if source_idx >= self.depth {
+                continue;
+            }
+            if let Some(next_idx) = self.find_source(picks.len(), source_idx + 1)? {
+                picks.push(next_idx);
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn solution(&self) -> Solution {
+        self.picks
+            .iter()
+            .map(|&source_idx| (source_idx < self.depth).then_some(source_idx))
+            .collect()
+    }
+}
@@ -0,0 +1,132 @@
+mod helpers;
+
+use fluent_fallback::{
+    generator::BundleGenerator,
+    types::{ResourceType, ToResourceId},
+    Localization,
+};
+use fluent_resmgr::{BundleError, EmbeddedResources, ResourceManager};
+use futures::StreamExt;
+use helpers::{unwrap_bundle, unwrap_errors};
+use std::borrow::Cow;
+use std::io;
+use unic_langid::langid;
+
+fn get_resources() -> EmbeddedResources {
+    vec![
+        (langid!("en-US"), "main.ftl", "hello = Hello"),
+        (langid!("en-US"), "extra.ftl", "extra = Extra"),
+        (langid!("pl"), "main.ftl", "hello = Witaj"),
+        (langid!("de"), "extra.ftl", "extra = Extra"),
+    ]
+    .into_iter()
+    .collect()
+}
+
+#[test]
+fn embedded_resources() {
+    let mut resources = get_resources();
+    assert_eq!(
+        resources.get(&langid!("pl"), "main.ftl"),
+        Some("hello = Witaj")
+    );
+    assert_eq!(resources.get(&langid!("pl"), "extra.ftl"), None);
+    assert_eq!(
+        resources.insert(langid!("pl"), "main.ftl", "hello = Cześć"),
+        Some("hello = Witaj")
+    );
+    assert_eq!(
+        resources.get(&langid!("pl"), "main.ftl"),
+        Some("hello = Cześć")
+    );
+}
+
+#[test]
+fn embedded_bundles_iter() {
+    let res_mgr = ResourceManager::embedded(get_resources());
+
+    let mut iter = res_mgr.bundles_iter(
+        vec![langid!("de"), langid!("en-US"), langid!("pl")].into_iter(),
+        vec![
+            "main.ftl".into(),
+            "extra.ftl".to_resource_id(ResourceType::Optional),
+        ],
+    );
+
+    // `de` is missing the required `main.ftl`.
+    let bundle = unwrap_bundle(iter.next().unwrap());
+    assert_eq!(bundle.locales, vec![langid!("en-US")]);
+    assert!(bundle.has_message("hello"));
+    assert!(bundle.has_message("extra"));
+
+    let (bundle, errors) = unwrap_errors(iter.next().unwrap());
+    assert_eq!(bundle.locales, vec![langid!("pl")]);
+    assert_eq!(
+        errors,
+        vec![BundleError::ResourceLoad {
+            id: "extra.ftl".into(),
+            kind: io::ErrorKind::NotFound,
+        }]
+    );
+
+    assert!(iter.next().is_none());
+}
+
+#[tokio::test]
+async fn embedded_bundles_stream() {
+    let res_mgr = ResourceManager::embedded(get_resources());
+
+    let locales: Vec<_> = res_mgr
+        .bundles_stream(
+            vec![langid!("de"), langid!("pl"), langid!("en-US")].into_iter(),
+            vec!["main.ftl".into()],
+        )
+        .map(|result| unwrap_bundle(result).locales[0].clone())
+        .collect()
+        .await;
+    assert_eq!(locales, vec![langid!("pl"), langid!("en-US")]);
+}
+
+#[tokio::test]
+async fn embedded_localization() {
+    let loc = Localization::with_env(
+        vec!["main.ftl".into(), "extra.ftl".into()],
+        false,
+        vec![langid!("pl"), langid!("en-US")],
+        ResourceManager::embedded(get_resources()),
+    );
+    let mut errors = vec![];
+
+    // `pl` is missing the required `extra.ftl`.
+    let value = loc.bundles().format_value("hello", None, &mut errors).await;
+    assert_eq!(value, Some(Cow::Borrowed("Hello")));
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn embedded_locales() {
+    let res_mgr = ResourceManager::embedded(get_resources());
+    assert_eq!(
+        res_mgr.locales().unwrap(),
+        vec![langid!("de"), langid!("en-US"), langid!("pl")]
+    );
+    assert_eq!(
+        res_mgr.locales_with(&["main.ftl".into()]).unwrap(),
+        vec![langid!("en-US"), langid!("pl")]
+    );
+    assert_eq!(
+        res_mgr
+            .locales_with(&[
+                "main.ftl".into(),
+                "extra.ftl".to_resource_id(ResourceType::Optional)
+            ])
+            .unwrap(),
+        vec![langid!("en-US"), langid!("pl")]
+    );
+    assert_eq!(
+        res_mgr
+            .locales_with(&["main.ftl".into(), "extra.ftl".into()])
+            .unwrap(),
+        vec![langid!("en-US")]
+    );
+}
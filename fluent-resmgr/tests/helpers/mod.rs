@@ -0,0 +1,75 @@
+// Each test includes its own copy of the helpers, and not every test
+// uses all of them.
+
+use async_trait::async_trait;
+use fluent_bundle::{FluentBundle, FluentResource};
+use fluent_fallback::generator::FluentBundleResult;
+use fluent_resmgr::{BundleError, FileFetcher};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io;
+use std::rc::Rc;
+
+/// Paths of all files requested from a [`MemoryFetcher`], in order.
+#[allow(dead_code)]
+pub type FetchLog = Rc<RefCell<Vec<String>>>;
+
+#[allow(dead_code)]
+#[derive(Default)]
+pub struct MemoryFetcher {
+    files: HashMap<&'static str, &'static str>,
+    pub fetched: FetchLog,
+}
+
+impl MemoryFetcher {
+    #[allow(dead_code)]
+    pub fn new(files: &[(&'static str, &'static str)]) -> Self {
+        Self {
+            files: files.iter().copied().collect(),
+            ..Default::default()
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl FileFetcher for MemoryFetcher {
+    fn fetch_sync(&self, path: &str) -> io::Result<String> {
+        self.fetched.borrow_mut().push(path.to_string());
+        self.files
+            .get(path)
+            .map(|source| source.to_string())
+            .ok_or_else(|| io::ErrorKind::NotFound.into())
+    }
+
+    async fn fetch(&self, path: &str) -> io::Result<String> {
+        self.fetch_sync(path)
+    }
+
+    fn read_dir(&self, path: &str) -> io::Result<Vec<String>> {
+        Ok(self
+            .files
+            .keys()
+            .filter_map(|file| file.strip_prefix(path)?.split('/').next())
+            .map(String::from)
+            .collect())
+    }
+}
+
+pub fn unwrap_bundle(
+    result: FluentBundleResult<Rc<FluentResource>, BundleError>,
+) -> FluentBundle<Rc<FluentResource>> {
+    match result {
+        Ok(bundle) => bundle,
+        Err((_, errors)) => panic!("Unexpected errors: {:?}", errors),
+    }
+}
+
+#[allow(dead_code)]
+pub fn unwrap_errors(
+    result: FluentBundleResult<Rc<FluentResource>, BundleError>,
+) -> (FluentBundle<Rc<FluentResource>>, Vec<BundleError>) {
+    match result {
+        Ok(_) => panic!("Expected errors"),
+        Err(err) => err,
+    }
+}
@@ -1,6 +1,18 @@
-use fluent_fallback::Localization;
-use fluent_resmgr::resource_manager::ResourceManager;
+mod helpers;
+
+use fluent_bundle::FluentError;
+use fluent_fallback::{
+    generator::BundleGenerator,
+    types::{ResourceType, ToResourceId},
+    Localization, LocalizationError,
+};
+use fluent_resmgr::{resource_manager::ResourceManager, BundleError};
+use futures::StreamExt;
+use helpers::{unwrap_bundle, unwrap_errors, MemoryFetcher};
 use std::borrow::Cow;
+use std::cell::RefCell;
+use std::io;
+use std::rc::Rc;
 use unic_langid::langid;
 
 #[test]
@@ -36,7 +48,7 @@ fn localization_format_value() {
 fn resmgr_get_bundle() {
     let res_mgr = ResourceManager::new("./tests/resources/{locale}/{res_id}".into());
 
-    let bundle = res_mgr.get_bundle(vec![langid!("en-US")], vec!["test.ftl".into()]);
+    let bundle = unwrap_bundle(res_mgr.get_bundle(vec![langid!("en-US")], vec!["test.ftl".into()]));
 
     let mut errors = vec![];
     let msg = bundle.get_message("hello-world").expect("Message exists");
@@ -44,3 +56,421 @@ fn resmgr_get_bundle() {
     let value = bundle.format_pattern(&pattern, None, &mut errors);
     assert_eq!(value, "Hello World");
 }
+
+#[test]
+fn resmgr_get_bundles_missing_resource() {
+    let fetcher = MemoryFetcher::new(&[("en-US/main.ftl", "hello = Hello")]);
+    let res_mgr = ResourceManager::with_fetcher("{locale}/{res_id}".into(), fetcher);
+
+    let mut bundles = res_mgr.get_bundles(
+        vec![langid!("en-US"), langid!("pl")],
+        vec!["main.ftl".into()],
+    );
+    assert!(unwrap_bundle(bundles.next().unwrap()).has_message("hello"));
+
+    let (bundle, errors) = unwrap_errors(bundles.next().unwrap());
+    assert!(!bundle.has_message("hello"));
+    assert_eq!(
+        errors,
+        vec![BundleError::ResourceLoad {
+            id: "main.ftl".into(),
+            kind: io::ErrorKind::NotFound,
+        }]
+    );
+    assert!(bundles.next().is_none());
+}
+
+#[tokio::test]
+async fn localization_format_value_async() {
+    let res_mgr = ResourceManager::new("./tests/resources/{locale}/{res_id}".into());
+
+    let loc = Localization::with_env(
+        vec!["test.ftl".into()],
+        false,
+        vec!["en-US".parse().unwrap(), "pl".parse().unwrap()],
+        res_mgr,
+    );
+    let bundles = loc.bundles();
+    let mut errors = vec![];
+
+    let value = bundles.format_value("hello-world", None, &mut errors).await;
+    assert_eq!(value, Some(Cow::Borrowed("Hello World")));
+
+    let value2 = bundles.format_value("new-message", None, &mut errors).await;
+    assert_eq!(value2, Some(Cow::Borrowed("Nowa Wiadomość")));
+
+    let value3 = bundles
+        .format_value("missing-message", None, &mut errors)
+        .await;
+    assert_eq!(value3, None);
+}
+
+#[tokio::test]
+async fn resmgr_bundles_stream() {
+    let fetcher = MemoryFetcher::new(&[
+        ("en-US/main.ftl", "hello = Hello"),
+        ("en-US/menu.ftl", "menu = Menu"),
+        ("pl/main.ftl", "hello = Witaj"),
+        ("pl/menu.ftl", "menu = Menu\nmenu-broken = {"),
+    ]);
+    let res_mgr = ResourceManager::with_fetcher("{locale}/{res_id}".into(), fetcher);
+
+    let mut stream = res_mgr.bundles_stream(
+        vec![langid!("en-US"), langid!("pl"), langid!("de")].into_iter(),
+        vec!["main.ftl".into(), "menu.ftl".into()],
+    );
+
+    let bundle = unwrap_bundle(stream.next().await.unwrap());
+    assert_eq!(bundle.locales, vec![langid!("en-US")]);
+    assert!(bundle.has_message("hello"));
+    assert!(bundle.has_message("menu"));
+
+    let (bundle, errors) = unwrap_errors(stream.next().await.unwrap());
+    assert_eq!(bundle.locales, vec![langid!("pl")]);
+    assert!(bundle.has_message("hello"));
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0],
+        BundleError::Fluent {
+            error: FluentError::ParserError(_),
+            ..
+        }
+    ));
+
+    // `de` is missing all of the required resources.
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn resmgr_bundles_stream_caches_resources() {
+    let fetcher = MemoryFetcher::new(&[("en-US/main.ftl", "hello = Hello")]);
+    let fetched = fetcher.fetched.clone();
+    let res_mgr = ResourceManager::with_fetcher("{locale}/{res_id}".into(), fetcher);
+
+    for _ in 0..2 {
+        let mut stream =
+            res_mgr.bundles_stream(vec![langid!("en-US")].into_iter(), vec!["main.ftl".into()]);
+        let bundle = unwrap_bundle(stream.next().await.unwrap());
+        assert!(bundle.has_message("hello"));
+    }
+    assert_eq!(*fetched.borrow(), vec!["en-US/main.ftl".to_string()]);
+}
+
+#[test]
+fn resmgr_bundles_iter() {
+    let fetcher = MemoryFetcher::new(&[
+        ("data/en-US/main.ftl", "hello = Hello"),
+        ("data/pl/main.ftl", "hello = Witaj\nhello-broken = {"),
+    ]);
+    let fetched = fetcher.fetched.clone();
+    let res_mgr = ResourceManager::with_fetcher("data/{locale}/{res_id}".into(), fetcher);
+
+    let mut iter = res_mgr.bundles_iter(
+        vec![langid!("en-US"), langid!("pl"), langid!("de")].into_iter(),
+        vec!["main.ftl".into()],
+    );
+
+    let bundle = unwrap_bundle(iter.next().unwrap());
+    assert_eq!(bundle.locales, vec![langid!("en-US")]);
+    assert!(bundle.has_message("hello"));
+
+    let (bundle, errors) = unwrap_errors(iter.next().unwrap());
+    assert!(bundle.has_message("hello"));
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0],
+        BundleError::Fluent {
+            error: FluentError::ParserError(_),
+            ..
+        }
+    ));
+
+    // `de` is missing all of the required resources.
+    assert!(iter.next().is_none());
+
+    // Resources loaded by the iterator are cached, along with their parse
+    // errors and the files which are missing.
+    let mut iter = res_mgr.bundles_iter(
+        vec![langid!("en-US"), langid!("pl"), langid!("de")].into_iter(),
+        vec!["main.ftl".into()],
+    );
+    assert!(unwrap_bundle(iter.next().unwrap()).has_message("hello"));
+    let (_, errors) = unwrap_errors(iter.next().unwrap());
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0],
+        BundleError::Fluent {
+            error: FluentError::ParserError(_),
+            ..
+        }
+    ));
+    assert!(iter.next().is_none());
+    assert_eq!(
+        *fetched.borrow(),
+        vec![
+            "data/en-US/main.ftl".to_string(),
+            "data/pl/main.ftl".to_string(),
+            "data/de/main.ftl".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn resmgr_bundles_iter_optional_resources() {
+    let fetcher = MemoryFetcher::new(&[
+        ("en-US/main.ftl", "hello = Hello"),
+        ("pl/main.ftl", "hello = Witaj"),
+        ("pl/extra.ftl", "extra = Dodatkowe"),
+        ("de/extra.ftl", "extra = Extra"),
+    ]);
+    let res_mgr = ResourceManager::with_fetcher("{locale}/{res_id}".into(), fetcher);
+
+    let mut iter = res_mgr.bundles_iter(
+        vec![langid!("de"), langid!("en-US"), langid!("pl")].into_iter(),
+        vec![
+            "main.ftl".into(),
+            "extra.ftl".to_resource_id(ResourceType::Optional),
+        ],
+    );
+
+    // `de` is skipped, because the required `main.ftl` is missing.
+    let (bundle, errors) = unwrap_errors(iter.next().unwrap());
+    assert_eq!(bundle.locales, vec![langid!("en-US")]);
+    assert!(bundle.has_message("hello"));
+    assert!(!bundle.has_message("extra"));
+    assert_eq!(
+        errors,
+        vec![BundleError::ResourceLoad {
+            id: "extra.ftl".into(),
+            kind: io::ErrorKind::NotFound,
+        }]
+    );
+
+    let bundle = unwrap_bundle(iter.next().unwrap());
+    assert_eq!(bundle.locales, vec![langid!("pl")]);
+    assert!(bundle.has_message("hello"));
+    assert!(bundle.has_message("extra"));
+
+    assert!(iter.next().is_none());
+}
+
+#[tokio::test]
+async fn resmgr_bundles_stream_optional_resources() {
+    let fetcher = MemoryFetcher::new(&[
+        ("en-US/main.ftl", "hello = Hello"),
+        ("pl/main.ftl", "hello = Witaj"),
+        ("pl/extra.ftl", "extra = Dodatkowe"),
+        ("de/extra.ftl", "extra = Extra"),
+    ]);
+    let res_mgr = ResourceManager::with_fetcher("{locale}/{res_id}".into(), fetcher);
+
+    let locales: Vec<_> = res_mgr
+        .bundles_stream(
+            vec![langid!("de"), langid!("en-US"), langid!("pl")].into_iter(),
+            vec![
+                "main.ftl".into(),
+                "extra.ftl".to_resource_id(ResourceType::Optional),
+            ],
+        )
+        .map(|result| match result {
+            Ok(bundle) => (bundle.locales[0].clone(), 0),
+            Err((bundle, errors)) => (bundle.locales[0].clone(), errors.len()),
+        })
+        .collect()
+        .await;
+
+    assert_eq!(locales, vec![(langid!("en-US"), 1), (langid!("pl"), 0)]);
+}
+
+#[test]
+fn localization_optional_resources() {
+    let fetcher = MemoryFetcher::new(&[
+        ("en-US/main.ftl", "hello = Hello"),
+        ("pl/main.ftl", "hello = Witaj"),
+        ("pl/extra.ftl", "extra = Dodatkowe"),
+    ]);
+    let res_mgr = ResourceManager::with_fetcher("{locale}/{res_id}".into(), fetcher);
+
+    let loc = Localization::with_env(
+        vec![
+            "main.ftl".into(),
+            "extra.ftl".to_resource_id(ResourceType::Optional),
+        ],
+        true,
+        vec![langid!("en-US"), langid!("pl")],
+        res_mgr,
+    );
+    let bundles = loc.bundles();
+    let mut errors = vec![];
+
+    let value = bundles
+        .format_value_sync("hello", None, &mut errors)
+        .unwrap();
+    assert_eq!(value, Some(Cow::Borrowed("Hello")));
+
+    let value = bundles
+        .format_value_sync("extra", None, &mut errors)
+        .unwrap();
+    assert_eq!(value, Some(Cow::Borrowed("Dodatkowe")));
+}
+
+#[test]
+fn localization_strict() {
+    let fetcher = MemoryFetcher::new(&[
+        ("pl/main.ftl", "hello = Witaj\nbroken = {"),
+        ("en-US/main.ftl", "hello = Hello\nbroken = {"),
+        ("en-US/extra.ftl", "extra = Extra"),
+    ]);
+    let res_mgr = ResourceManager::with_fetcher("{locale}/{res_id}".into(), fetcher);
+
+    let mut loc = Localization::with_env(
+        vec![
+            "main.ftl".into(),
+            "extra.ftl".to_resource_id(ResourceType::Optional),
+        ],
+        true,
+        vec![langid!("pl"), langid!("en-US")],
+        res_mgr,
+    );
+
+    // The resource of the primary locale fails to parse, while the missing
+    // optional resource doesn't matter.
+    for _ in 0..2 {
+        let errors = loc.strict().format_value_sync("hello", None).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            LocalizationError::Bundle {
+                locale,
+                res_id: Some(res_id),
+                error: FluentError::ParserError(_),
+            } if *locale == langid!("pl") && res_id == "main.ftl"
+        ));
+
+        // The parse error is cached along with the resource, and still
+        // reported once the bundles are rebuilt.
+        loc.on_change();
+    }
+}
+
+#[test]
+fn localization_error_sink_bundle_errors() {
+    let fetcher = MemoryFetcher::new(&[
+        ("pl/main.ftl", "hello = Witaj\nbroken = {"),
+        ("en-US/main.ftl", "hello = Hello\nbroken = {"),
+    ]);
+    let res_mgr = ResourceManager::with_fetcher("{locale}/{res_id}".into(), fetcher);
+
+    let mut loc = Localization::with_env(
+        vec!["main.ftl".into()],
+        true,
+        vec![langid!("pl"), langid!("en-US")],
+        res_mgr,
+    );
+    let reported = Rc::new(RefCell::new(vec![]));
+    let sink = reported.clone();
+    loc.set_error_sink(move |error: &LocalizationError| {
+        if let LocalizationError::Bundle { locale, res_id, .. } = error {
+            sink.borrow_mut().push((locale.clone(), res_id.clone()));
+        }
+    });
+
+    // The same parse error of both locales is reported for each of them,
+    // and only once.
+    let mut errors = vec![];
+    for _ in 0..2 {
+        let value = loc
+            .bundles()
+            .format_value_sync("missing", None, &mut errors)
+            .unwrap();
+        assert_eq!(value, None);
+    }
+    assert_eq!(
+        *reported.borrow(),
+        vec![
+            (langid!("pl"), Some("main.ftl".to_string())),
+            (langid!("en-US"), Some("main.ftl".to_string())),
+        ]
+    );
+}
+
+#[test]
+fn localization_changes_reuse_resources() {
+    let fetcher = MemoryFetcher::new(&[
+        ("en-US/main.ftl", "hello = Hello"),
+        ("en-US/extra.ftl", "extra = Extra"),
+        ("pl/main.ftl", "hello = Witaj"),
+    ]);
+    let fetched = fetcher.fetched.clone();
+    let res_mgr = ResourceManager::with_fetcher("{locale}/{res_id}".into(), fetcher);
+
+    let mut loc = Localization::with_env(
+        vec!["main.ftl".into()],
+        true,
+        vec![langid!("pl"), langid!("en-US")],
+        res_mgr,
+    );
+    let mut errors = vec![];
+    // Looking up a missing message loads the bundles of all locales.
+    let value = loc
+        .bundles()
+        .format_value_sync("extra", None, &mut errors)
+        .unwrap();
+    assert_eq!(value, None);
+    assert_eq!(fetched.borrow().len(), 2);
+
+    // Only the added resource is loaded when the bundles are rebuilt.
+    fetched.borrow_mut().clear();
+    loc.add_resource_id("extra.ftl".to_resource_id(ResourceType::Optional));
+    let value = loc
+        .bundles()
+        .format_value_sync("extra", None, &mut errors)
+        .unwrap();
+    assert_eq!(value, Some(Cow::Borrowed("Extra")));
+    assert_eq!(
+        *fetched.borrow(),
+        vec!["pl/extra.ftl".to_string(), "en-US/extra.ftl".to_string()]
+    );
+
+    // The bundle of "pl", which lacks the added resource, was kept.
+    let res_id = "extra.ftl".into();
+    assert!(!loc.generator().has_resource(&langid!("pl"), &res_id));
+    assert!(loc.generator().has_resource(&langid!("en-US"), &res_id));
+}
+
+#[test]
+fn resmgr_locales() {
+    let res_mgr = ResourceManager::new("./tests/resources/{locale}/{res_id}".into());
+    // `overrides` isn't a valid language identifier.
+    assert_eq!(
+        res_mgr.locales().unwrap(),
+        vec![langid!("en-US"), langid!("pl")]
+    );
+    assert_eq!(
+        res_mgr.locales_with(&["test.ftl".into()]).unwrap(),
+        vec![langid!("en-US"), langid!("pl")]
+    );
+    assert!(res_mgr
+        .locales_with(&["missing.ftl".into()])
+        .unwrap()
+        .is_empty());
+
+    // The `{locale}` segment may have a prefix and a suffix.
+    let fetcher = MemoryFetcher::new(&[
+        ("l10n/main-pl.ftl", "hello = Witaj"),
+        ("l10n/main-en-US.ftl", "hello = Hello"),
+        ("l10n/other-de.ftl", "hello = Hallo"),
+        ("l10n/main-README.md", ""),
+    ]);
+    let res_mgr = ResourceManager::with_fetcher("l10n/main-{locale}.ftl".into(), fetcher);
+    assert_eq!(
+        res_mgr.locales().unwrap(),
+        vec![langid!("en-US"), langid!("pl")]
+    );
+
+    let res_mgr = ResourceManager::new("./tests/resources/".into());
+    assert_eq!(
+        res_mgr.locales().unwrap_err().kind(),
+        io::ErrorKind::InvalidInput
+    );
+}
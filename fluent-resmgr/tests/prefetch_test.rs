@@ -0,0 +1,140 @@
+mod helpers;
+
+use async_trait::async_trait;
+use fluent_fallback::{
+    generator::{BundleGenerator, BundleStream},
+    Localization,
+};
+use fluent_resmgr::{FileFetcher, ResourceManager};
+use futures::StreamExt;
+use helpers::{unwrap_bundle, MemoryFetcher};
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+use unic_langid::{langid, LanguageIdentifier};
+
+/// Stays pending for the given number of polls, waking itself up in between.
+struct Delay(usize);
+
+impl Future for Delay {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 == 0 {
+            return Poll::Ready(());
+        }
+        self.0 -= 1;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+/// A [`MemoryFetcher`] whose files take some polls to fetch asynchronously.
+struct DelayedFetcher {
+    inner: MemoryFetcher,
+    delays: HashMap<&'static str, usize>,
+    in_flight: Cell<usize>,
+    max_in_flight: Rc<Cell<usize>>,
+}
+
+impl DelayedFetcher {
+    fn new(files: &[(&'static str, &'static str, usize)]) -> Self {
+        let sources: Vec<_> = files
+            .iter()
+            .map(|(path, source, _)| (*path, *source))
+            .collect();
+        Self {
+            inner: MemoryFetcher::new(&sources),
+            delays: files
+                .iter()
+                .map(|(path, _, delay)| (*path, *delay))
+                .collect(),
+            in_flight: Cell::new(0),
+            max_in_flight: Default::default(),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl FileFetcher for DelayedFetcher {
+    fn fetch_sync(&self, path: &str) -> io::Result<String> {
+        self.inner.fetch_sync(path)
+    }
+
+    async fn fetch(&self, path: &str) -> io::Result<String> {
+        self.in_flight.set(self.in_flight.get() + 1);
+        self.max_in_flight
+            .set(self.max_in_flight.get().max(self.in_flight.get()));
+        Delay(self.delays.get(path).copied().unwrap_or_default()).await;
+        self.in_flight.set(self.in_flight.get() - 1);
+        self.inner.fetch_sync(path)
+    }
+}
+
+// The first locales take the longest to load.
+fn delayed_fetcher() -> DelayedFetcher {
+    DelayedFetcher::new(&[
+        ("pl/main.ftl", "hello = Witaj", 30),
+        ("de/main.ftl", "hello = Hallo", 20),
+        ("en-US/main.ftl", "hello = Hello", 10),
+    ])
+}
+
+fn locales() -> Vec<LanguageIdentifier> {
+    vec![langid!("pl"), langid!("de"), langid!("en-US")]
+}
+
+#[tokio::test]
+async fn resmgr_prefetch_parallel() {
+    let fetcher = delayed_fetcher();
+    let max_in_flight = fetcher.max_in_flight.clone();
+    let fetched = fetcher.inner.fetched.clone();
+    let mut res_mgr = ResourceManager::with_fetcher("{locale}/{res_id}".into(), fetcher);
+    res_mgr.set_prefetch_parallelism(2);
+
+    let mut stream = res_mgr.bundles_stream(locales().into_iter(), vec!["main.ftl".into()]);
+    stream.prefetch_async().await;
+    assert_eq!(max_in_flight.get(), 2);
+    assert_eq!(fetched.borrow().len(), 3);
+
+    // Bundles are yielded in the order of the locales.
+    let locales: Vec<_> = stream
+        .map(|bundle| unwrap_bundle(bundle).locales[0].clone())
+        .collect()
+        .await;
+    assert_eq!(locales, self::locales());
+    assert_eq!(fetched.borrow().len(), 3);
+}
+
+#[tokio::test]
+async fn resmgr_prefetch_sequential() {
+    let fetcher = delayed_fetcher();
+    let max_in_flight = fetcher.max_in_flight.clone();
+    let res_mgr = ResourceManager::with_fetcher("{locale}/{res_id}".into(), fetcher);
+
+    let mut stream = res_mgr.bundles_stream(locales().into_iter(), vec!["main.ftl".into()]);
+    stream.prefetch_async().await;
+    assert_eq!(max_in_flight.get(), 1);
+    assert_eq!(stream.count().await, 3);
+}
+
+#[tokio::test]
+async fn localization_prefetch_parallel() {
+    let fetcher = delayed_fetcher();
+    let max_in_flight = fetcher.max_in_flight.clone();
+    let mut res_mgr = ResourceManager::with_fetcher("{locale}/{res_id}".into(), fetcher);
+    res_mgr.set_prefetch_parallelism(3);
+
+    let mut loc = Localization::with_env(vec!["main.ftl".into()], false, locales(), res_mgr);
+    loc.prefetch_async().await;
+    assert_eq!(max_in_flight.get(), 3);
+
+    let mut errors = vec![];
+    let value = loc.bundles().format_value("hello", None, &mut errors).await;
+    assert_eq!(value, Some(Cow::Borrowed("Witaj")));
+}
@@ -0,0 +1,212 @@
+mod helpers;
+
+use fluent_bundle::{FluentBundle, FluentResource};
+use fluent_fallback::{
+    generator::BundleGenerator,
+    types::{ResourceType, ToResourceId},
+    Localization,
+};
+use fluent_resmgr::{BundleError, FileSource, ResourceRegistry};
+use futures::StreamExt;
+use helpers::{unwrap_bundle, unwrap_errors, FetchLog, MemoryFetcher};
+use std::borrow::Cow;
+use std::io;
+use std::rc::Rc;
+use unic_langid::{langid, LanguageIdentifier};
+
+fn get_registry() -> (ResourceRegistry<MemoryFetcher>, FetchLog) {
+    let fetcher = MemoryFetcher::new(&[
+        ("user/pl/main.ftl", "hello = Witaj!"),
+        ("core/en-US/main.ftl", "hello = Hello"),
+        ("core/pl/main.ftl", "hello = Witaj"),
+        ("core/de/main.ftl", "hello = Hallo"),
+        ("plugins/en-US/plugin.ftl", "plugin = Plugin"),
+        ("plugins/pl/plugin.ftl", "plugin = Wtyczka"),
+    ]);
+    let fetched = fetcher.fetched.clone();
+    let mut registry = ResourceRegistry::with_fetcher(fetcher);
+    registry.register_source(FileSource::new(
+        "user".to_string(),
+        "user/{locale}/{res_id}".to_string(),
+        vec![langid!("pl")],
+    ));
+    registry.register_source(FileSource::new(
+        "core".to_string(),
+        "core/{locale}/{res_id}".to_string(),
+        vec![langid!("en-US"), langid!("pl"), langid!("de")],
+    ));
+    registry.register_source(FileSource::new(
+        "plugins".to_string(),
+        "plugins/{locale}/{res_id}".to_string(),
+        vec![langid!("en-US"), langid!("pl")],
+    ));
+    (registry, fetched)
+}
+
+#[test]
+fn registry_sources() {
+    let (mut registry, _) = get_registry();
+
+    let names: Vec<_> = registry.sources().iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["user", "core", "plugins"]);
+    assert_eq!(
+        registry.locales(),
+        vec![langid!("pl"), langid!("en-US"), langid!("de")]
+    );
+
+    // Registering a source with an existing name replaces it.
+    registry.register_source(FileSource::new(
+        "user".to_string(),
+        "user/{locale}/{res_id}".to_string(),
+        vec![langid!("fr")],
+    ));
+    let names: Vec<_> = registry.sources().iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["core", "plugins", "user"]);
+    assert_eq!(
+        registry.source("user").map(|s| s.locales.clone()),
+        Some(vec![langid!("fr")])
+    );
+
+    let removed = registry.remove_source("core").expect("Source exists");
+    assert_eq!(removed.path_scheme, "core/{locale}/{res_id}");
+    assert!(registry.remove_source("core").is_none());
+    assert_eq!(
+        registry.locales(),
+        vec![langid!("en-US"), langid!("pl"), langid!("fr")]
+    );
+}
+
+#[test]
+fn registry_bundles_iter() {
+    let (registry, fetched) = get_registry();
+
+    let mut iter = registry.bundles_iter(
+        vec![
+            langid!("pl"),
+            langid!("de"),
+            langid!("fr"),
+            langid!("en-US"),
+        ]
+        .into_iter(),
+        vec![
+            "main.ftl".into(),
+            "plugin.ftl".to_resource_id(ResourceType::Optional),
+        ],
+    );
+
+    // `main.ftl` comes from the `user` source, and `plugin.ftl` from `plugins`.
+    let bundle = unwrap_bundle(iter.next().unwrap());
+    assert_eq!(bundle.locales, vec![langid!("pl")]);
+    assert_eq!(format(&bundle, "hello"), "Witaj!");
+    assert_eq!(format(&bundle, "plugin"), "Wtyczka");
+
+    // Files are only read once a bundle needs them.
+    assert_eq!(fetched.borrow().len(), 4);
+
+    // `core` has an alternative version of `main.ftl`.
+    let bundle = unwrap_bundle(iter.next().unwrap());
+    assert_eq!(bundle.locales, vec![langid!("pl")]);
+    assert_eq!(format(&bundle, "hello"), "Witaj");
+    assert_eq!(format(&bundle, "plugin"), "Wtyczka");
+    assert_eq!(fetched.borrow().len(), 5);
+
+    // `plugins` don't provide `de`.
+    let (bundle, errors) = unwrap_errors(iter.next().unwrap());
+    assert_eq!(bundle.locales, vec![langid!("de")]);
+    assert_eq!(format(&bundle, "hello"), "Hallo");
+    assert_eq!(
+        errors,
+        vec![BundleError::ResourceLoad {
+            id: "plugin.ftl".into(),
+            kind: io::ErrorKind::NotFound,
+        }]
+    );
+
+    // No source provides `fr`, so it is skipped.
+    let bundle = unwrap_bundle(iter.next().unwrap());
+    assert_eq!(bundle.locales, vec![langid!("en-US")]);
+    assert_eq!(format(&bundle, "hello"), "Hello");
+    assert_eq!(format(&bundle, "plugin"), "Plugin");
+
+    assert!(iter.next().is_none());
+
+    // Sources which don't provide a locale are never queried.
+    assert_eq!(
+        *fetched.borrow(),
+        vec![
+            "user/pl/main.ftl",
+            "user/pl/plugin.ftl",
+            "core/pl/plugin.ftl",
+            "plugins/pl/plugin.ftl",
+            "core/pl/main.ftl",
+            "plugins/pl/main.ftl",
+            "core/de/main.ftl",
+            "core/de/plugin.ftl",
+            "core/en-US/main.ftl",
+            "core/en-US/plugin.ftl",
+            "plugins/en-US/plugin.ftl",
+            "plugins/en-US/main.ftl",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn registry_bundles_stream() {
+    let (registry, _) = get_registry();
+
+    let bundles: Vec<_> = registry
+        .bundles_stream(
+            vec![
+                langid!("pl"),
+                langid!("de"),
+                langid!("fr"),
+                langid!("en-US"),
+            ]
+            .into_iter(),
+            vec!["main.ftl".into(), "plugin.ftl".into()],
+        )
+        .map(unwrap_bundle)
+        .collect()
+        .await;
+
+    let locales: Vec<LanguageIdentifier> = bundles
+        .iter()
+        .map(|bundle| bundle.locales[0].clone())
+        .collect();
+    assert_eq!(
+        locales,
+        vec![langid!("pl"), langid!("pl"), langid!("en-US")]
+    );
+    assert_eq!(format(&bundles[0], "hello"), "Witaj!");
+    assert_eq!(format(&bundles[1], "hello"), "Witaj");
+    assert_eq!(format(&bundles[2], "plugin"), "Plugin");
+}
+
+#[test]
+fn registry_localization() {
+    let (registry, _) = get_registry();
+
+    let loc = Localization::with_env(
+        vec!["main.ftl".into(), "plugin.ftl".into()],
+        true,
+        vec![langid!("de"), langid!("pl")],
+        registry,
+    );
+    let bundles = loc.bundles();
+    let mut errors = vec![];
+
+    let value = bundles
+        .format_value_sync("hello", None, &mut errors)
+        .unwrap();
+    assert_eq!(value, Some(Cow::Borrowed("Witaj!")));
+    assert!(errors.is_empty());
+}
+
+fn format(bundle: &FluentBundle<Rc<FluentResource>>, id: &str) -> String {
+    let msg = bundle.get_message(id).expect("Message exists");
+    let pattern = msg.value().expect("Message has a value");
+    let mut errors = vec![];
+    bundle
+        .format_pattern(pattern, None, &mut errors)
+        .to_string()
+}
@@ -0,0 +1,159 @@
+mod helpers;
+
+use fluent_fallback::{generator::BundleGenerator, Localization, LocalizationChange};
+use fluent_resmgr::ResourceManager;
+use helpers::{unwrap_bundle, MemoryFetcher};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, SystemTime};
+use unic_langid::langid;
+
+/// A directory with resource files, removed once dropped.
+struct TempResources(PathBuf);
+
+impl TempResources {
+    fn new(name: &str) -> Self {
+        let dir =
+            std::env::temp_dir().join(format!("fluent-resmgr-{}-{}", name, std::process::id()));
+        fs::create_dir_all(dir.join("en-US")).unwrap();
+        Self(dir)
+    }
+
+    fn path_scheme(&self) -> String {
+        format!("{}/{{locale}}/{{res_id}}", self.0.display())
+    }
+
+    /// Writes a file, moving its modification time forward by `secs`,
+    /// so the change is detected regardless of the timestamp resolution.
+    fn write(&self, path: &str, source: &str, secs: u64) {
+        let path = self.0.join(path);
+        fs::write(&path, source).unwrap();
+        set_modified(&path, SystemTime::now() + Duration::from_secs(secs));
+    }
+}
+
+impl Drop for TempResources {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn set_modified(path: &Path, time: SystemTime) {
+    fs::File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(time)
+        .unwrap();
+}
+
+#[test]
+fn resmgr_reload() {
+    let resources = TempResources::new("reload");
+    resources.write("en-US/main.ftl", "hello = Hello", 0);
+    let res_mgr = ResourceManager::new(resources.path_scheme());
+
+    let mut loc = Localization::with_env(
+        vec!["main.ftl".into()],
+        true,
+        vec![langid!("en-US")],
+        res_mgr,
+    );
+    let mut errors = vec![];
+
+    let value = loc
+        .bundles()
+        .format_value_sync("hello", None, &mut errors)
+        .unwrap();
+    assert_eq!(value, Some(Cow::Borrowed("Hello")));
+    assert!(!loc.reload());
+
+    resources.write("en-US/main.ftl", "hello = Hello again", 10);
+    assert!(loc.reload());
+
+    let value = loc
+        .bundles()
+        .format_value_sync("hello", None, &mut errors)
+        .unwrap();
+    assert_eq!(value, Some(Cow::Borrowed("Hello again")));
+    assert!(errors.is_empty());
+
+    // Removed files are dropped as well.
+    fs::remove_file(resources.0.join("en-US/main.ftl")).unwrap();
+    assert!(loc.reload());
+    let value = loc
+        .bundles()
+        .format_value_sync("hello", None, &mut errors)
+        .unwrap();
+    assert_eq!(value, None);
+    assert!(!loc.reload());
+
+    // Missing files are picked up once they're created.
+    resources.write("en-US/main.ftl", "hello = Hello once more", 20);
+    assert!(loc.reload());
+    let value = loc
+        .bundles()
+        .format_value_sync("hello", None, &mut errors)
+        .unwrap();
+    assert_eq!(value, Some(Cow::Borrowed("Hello once more")));
+}
+
+#[test]
+fn localization_reload() {
+    let resources = TempResources::new("localization-reload");
+    resources.write("en-US/main.ftl", "hello = Hello", 0);
+    let res_mgr = ResourceManager::new(resources.path_scheme());
+
+    let mut loc = Localization::with_env(
+        vec!["main.ftl".into()],
+        true,
+        vec![langid!("en-US")],
+        res_mgr,
+    );
+    let changes = Rc::new(RefCell::new(vec![]));
+    let observer = changes.clone();
+    loc.subscribe(move |change: &LocalizationChange| observer.borrow_mut().push(change.clone()));
+    let mut errors = vec![];
+
+    let value = loc
+        .bundles()
+        .format_value_sync("hello", None, &mut errors)
+        .unwrap();
+    assert_eq!(value, Some(Cow::Borrowed("Hello")));
+    assert!(!loc.reload());
+    assert!(changes.borrow().is_empty());
+
+    resources.write("en-US/main.ftl", "hello = Hello again", 10);
+    assert!(loc.reload());
+    assert_eq!(
+        *changes.borrow(),
+        vec![LocalizationChange::ResourcesReloaded]
+    );
+
+    let value = loc
+        .bundles()
+        .format_value_sync("hello", None, &mut errors)
+        .unwrap();
+    assert_eq!(value, Some(Cow::Borrowed("Hello again")));
+}
+
+#[test]
+fn resmgr_reload_unsupported() {
+    let fetcher = MemoryFetcher::new(&[("en-US/main.ftl", "hello = Hello")]);
+    let fetched = fetcher.fetched.clone();
+    let res_mgr = ResourceManager::with_fetcher("{locale}/{res_id}".into(), fetcher);
+
+    let mut iter =
+        res_mgr.bundles_iter(vec![langid!("en-US")].into_iter(), vec!["main.ftl".into()]);
+    assert!(unwrap_bundle(iter.next().unwrap()).has_message("hello"));
+
+    // Without modification times, resources are kept.
+    assert!(!res_mgr.reload());
+    let mut iter =
+        res_mgr.bundles_iter(vec![langid!("en-US")].into_iter(), vec!["main.ftl".into()]);
+    assert!(unwrap_bundle(iter.next().unwrap()).has_message("hello"));
+    assert_eq!(fetched.borrow().len(), 1);
+}
@@ -0,0 +1,2 @@
+hello-world = Witaj Świecie!
+new-message = Nowa Wiadomość!
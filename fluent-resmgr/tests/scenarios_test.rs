@@ -0,0 +1,196 @@
+use async_trait::async_trait;
+use fluent_bundle::{FluentArgs, FluentValue};
+use fluent_fallback::{
+    types::{L10nKey, L10nMessage},
+    Localization, LocalizationError,
+};
+use fluent_resmgr::{FileFetcher, FileSource, ResourceRegistry};
+use fluent_testing::scenarios::structs::{Query, Scenario};
+use fluent_testing::{get_scenarios, MockFileSystem};
+use std::io;
+use unic_langid::LanguageIdentifier;
+
+#[derive(Default)]
+struct MockFetcher(MockFileSystem);
+
+#[async_trait(?Send)]
+impl FileFetcher for MockFetcher {
+    fn fetch_sync(&self, path: &str) -> io::Result<String> {
+        self.0.get_test_file_sync(path)
+    }
+
+    async fn fetch(&self, path: &str) -> io::Result<String> {
+        self.0.get_test_file_sync(path)
+    }
+}
+
+fn get_localization(
+    scenario: &Scenario,
+    sync: bool,
+) -> Localization<ResourceRegistry<MockFetcher>, Vec<LanguageIdentifier>> {
+    let mut registry = ResourceRegistry::with_fetcher(MockFetcher::default());
+    // Expected values assume the Linux variants of platform-specific messages.
+    registry.set_adapt_bundle(Some(|bundle| {
+        bundle
+            .add_function("PLATFORM", |_, _| FluentValue::from("linux"))
+            .expect("Failed to add a function.");
+    }));
+    // The scenarios leave the resource id out of their path schemes.
+    for source in &scenario.file_sources {
+        registry.register_source(FileSource::new(
+            source.name.clone(),
+            format!("{}{{res_id}}", source.path_scheme),
+            source.locales.iter().map(|l| l.parse().unwrap()).collect(),
+        ));
+    }
+    let locales = scenario
+        .locales
+        .iter()
+        .map(|l| l.parse().unwrap())
+        .collect();
+    Localization::with_env(scenario.res_ids.clone(), sync, locales, registry)
+}
+
+fn get_key(query: &Query) -> L10nKey<'_> {
+    L10nKey {
+        id: query.input.id.as_str().into(),
+        args: query.input.args.as_ref().map(|args| {
+            let mut result = FluentArgs::new();
+            for arg in args {
+                result.set(arg.id.as_str(), FluentValue::try_number(&arg.value));
+            }
+            result
+        }),
+        attributes: None,
+    }
+}
+
+fn strip_isolation(s: &str) -> String {
+    s.replace(['\u{2068}', '\u{2069}'], "")
+}
+
+/// Expected values are written with `\uXXXX` escapes of special characters.
+fn unescape(s: &Option<String>) -> Option<String> {
+    let s = s.as_deref()?;
+    let mut result = String::new();
+    let mut rest = s;
+    while let Some(idx) = rest.find("\\u") {
+        result.push_str(&rest[..idx]);
+        let code = u32::from_str_radix(&rest[idx + 2..idx + 6], 16).expect("Invalid escape");
+        result.push(char::from_u32(code).expect("Invalid code point"));
+        rest = &rest[idx + 6..];
+    }
+    result.push_str(rest);
+    Some(result)
+}
+
+fn check_query(
+    scenario: &Scenario,
+    query: &Query,
+    message: Option<&L10nMessage>,
+    errors: &[LocalizationError],
+) {
+    let context = query.exceptional_context;
+    let expected = match &query.output {
+        Some(expected) => expected,
+        None => return,
+    };
+
+    // Without any bundles, none of the messages of the scenario can be found.
+    let blocked = scenario
+        .queries
+        .0
+        .iter()
+        .any(|query| query.exceptional_context.blocks_bundle_generation());
+    if blocked {
+        assert!(
+            message.is_none(),
+            "{}: {} should be missing",
+            scenario.name,
+            query.input.id
+        );
+        return;
+    }
+
+    if context.causes_failed_value_lookup() {
+        assert!(
+            message.is_none(),
+            "{}: {} should be missing",
+            scenario.name,
+            query.input.id
+        );
+        // Bindings fall back on the id of a missing message.
+        assert_eq!(expected.value.as_deref(), Some(query.input.id.as_str()));
+    } else {
+        let message = message
+            .unwrap_or_else(|| panic!("{}: {} should be present", scenario.name, query.input.id));
+        assert_eq!(
+            message.value.as_deref().map(strip_isolation),
+            unescape(&expected.value),
+            "{}: {}",
+            scenario.name,
+            query.input.id
+        );
+        for attr in expected.attributes.iter().flatten() {
+            let value = message
+                .attributes
+                .iter()
+                .find(|a| a.name == attr.name)
+                .map(|a| strip_isolation(&a.value));
+            assert_eq!(
+                value,
+                unescape(&Some(attr.value.clone())),
+                "{}: {}",
+                scenario.name,
+                query.input.id
+            );
+        }
+    }
+
+    // Missing optional resources are reported as errors of the bundle
+    // whenever it's used, regardless of the message being looked up.
+    let has_errors = errors
+        .iter()
+        .any(|err| !matches!(err, LocalizationError::ResourceLoad { .. }));
+    if context.causes_reported_format_error() {
+        assert!(!errors.is_empty(), "{}: {}", scenario.name, query.input.id);
+    } else {
+        assert!(
+            !has_errors,
+            "{}: {} reported {:?}",
+            scenario.name, query.input.id, errors
+        );
+    }
+}
+
+#[test]
+fn scenarios_sync() {
+    for scenario in get_scenarios() {
+        let loc = get_localization(&scenario, true);
+        let bundles = loc.bundles();
+
+        for query in scenario.queries.iter() {
+            let keys = [get_key(query)];
+            let mut errors = vec![];
+            let messages = bundles
+                .format_messages_sync(&keys, &mut errors)
+                .expect("Localization is sync");
+            check_query(&scenario, query, messages[0].as_ref(), &errors);
+        }
+    }
+}
+
+#[tokio::test]
+async fn scenarios_async() {
+    for scenario in get_scenarios() {
+        let loc = get_localization(&scenario, false);
+        let bundles = loc.bundles();
+
+        for query in scenario.queries.iter() {
+            let keys = [get_key(query)];
+            let mut errors = vec![];
+            let messages = bundles.format_messages(&keys, &mut errors).await;
+            check_query(&scenario, query, messages[0].as_ref(), &errors);
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use fluent_resmgr::solver::ProblemSolver;
+
+fn solve_all(solver: &mut ProblemSolver, available: &[&[bool]]) -> Vec<Vec<Option<usize>>> {
+    let mut test = |res_idx: usize, source_idx: usize| available[source_idx][res_idx];
+    std::iter::from_fn(|| solver.next_sync(&mut test)).collect()
+}
+
+#[test]
+fn solver_enumerates_in_preference_order() {
+    let mut solver = ProblemSolver::new(2, vec![false, false]);
+    let solutions = solve_all(&mut solver, &[&[true, true], &[true, true]]);
+    assert_eq!(
+        solutions,
+        vec![
+            vec![Some(0), Some(0)],
+            vec![Some(0), Some(1)],
+            vec![Some(1), Some(0)],
+            vec![Some(1), Some(1)],
+        ]
+    );
+}
+
+#[test]
+fn solver_missing_required_resource() {
+    let mut solver = ProblemSolver::new(2, vec![false, false]);
+    assert!(solve_all(&mut solver, &[&[true, false], &[true, false]]).is_empty());
+}
+
+#[test]
+fn solver_missing_optional_resource() {
+    let mut solver = ProblemSolver::new(2, vec![false, true]);
+    let solutions = solve_all(&mut solver, &[&[false, false], &[true, false]]);
+    assert_eq!(solutions, vec![vec![Some(1), None]]);
+}
+
+#[test]
+fn solver_try_next_requests_cells() {
+    let mut solver = ProblemSolver::new(1, vec![false]);
+    assert_eq!(solver.try_next(), Err((0, 0)));
+    // The solver doesn't advance until the cell is recorded.
+    assert_eq!(solver.try_next(), Err((0, 0)));
+    solver.set_cell(0, 0, true);
+    assert_eq!(solver.try_next(), Ok(Some(vec![Some(0)])));
+    assert_eq!(solver.try_next(), Ok(None));
+}
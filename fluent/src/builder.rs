@@ -0,0 +1,114 @@
+use std::borrow::Borrow;
+
+use fluent_bundle::bundle::IsolationStrategy;
+use fluent_bundle::{FluentBundle, FluentError, FluentResource};
+use unic_langid::LanguageIdentifier;
+
+/// A convenience builder for [`FluentBundle`].
+///
+/// The builder collects the locales, resources and settings of a bundle
+/// and applies them in one go. When the `fluent-pseudo` feature is enabled,
+/// bundles built for a pseudolocale such as `en-XA` or `ar-XB` will have
+/// the matching pseudolocalization transform set.
+///
+/// # Example
+///
+/// ```
+/// use fluent::{FluentBundleBuilder, FluentResource};
+/// use unic_langid::langid;
+///
+/// let res = FluentResource::try_new("hello = Hello, { $name }!".to_string())
+///     .expect("Failed to parse an FTL string.");
+///
+/// let mut errors = vec![];
+/// let bundle = FluentBundleBuilder::new(vec![langid!("en-US")])
+///     .add_resource(res)
+///     .use_isolating(false)
+///     .build(&mut errors);
+///
+/// assert!(errors.is_empty());
+/// assert!(bundle.has_message("hello"));
+/// ```
+pub struct FluentBundleBuilder<R> {
+    locales: Vec<LanguageIdentifier>,
+    resources: Vec<R>,
+    isolation_strategy: IsolationStrategy,
+    #[cfg_attr(not(feature = "fluent-pseudo"), allow(dead_code))]
+    pseudo: bool,
+}
+
+impl<R> FluentBundleBuilder<R> {
+    /// Creates a builder for a bundle of the given locales, in order of
+    /// preference, with no resources and Unicode isolation enabled.
+    pub fn new(locales: Vec<LanguageIdentifier>) -> Self {
+        Self {
+            locales,
+            resources: vec![],
+            isolation_strategy: IsolationStrategy::default(),
+            pseudo: true,
+        }
+    }
+
+    /// Queues a resource to be added to the bundle.
+    pub fn add_resource(mut self, r: R) -> Self {
+        self.resources.push(r);
+        self
+    }
+
+    /// See [`FluentBundle::set_use_isolating`](fluent_bundle::bundle::FluentBundle::set_use_isolating).
+    pub fn use_isolating(mut self, value: bool) -> Self {
+        self.isolation_strategy = if value {
+            IsolationStrategy::Isolate
+        } else {
+            IsolationStrategy::None
+        };
+        self
+    }
+
+    /// See [`FluentBundle::set_isolation_strategy`](fluent_bundle::bundle::FluentBundle::set_isolation_strategy).
+    pub fn isolation_strategy(mut self, strategy: IsolationStrategy) -> Self {
+        self.isolation_strategy = strategy;
+        self
+    }
+
+    /// Controls whether a pseudolocalization transform is set when the
+    /// first locale of the bundle is a pseudolocale.
+    ///
+    /// This is enabled by default and has no effect unless the
+    /// `fluent-pseudo` feature is enabled.
+    pub fn pseudolocalize(mut self, value: bool) -> Self {
+        self.pseudo = value;
+        self
+    }
+
+    /// Constructs the bundle.
+    ///
+    /// All queued resources are added to the bundle even if some of them
+    /// override existing entries, in which case the errors are accumulated
+    /// in the `Vec<FluentError>` passed as the argument.
+    pub fn build(self, errors: &mut Vec<FluentError>) -> FluentBundle<R>
+    where
+        R: Borrow<FluentResource>,
+    {
+        let mut bundle = FluentBundle::new(self.locales);
+        bundle.set_isolation_strategy(self.isolation_strategy);
+
+        #[cfg(feature = "fluent-pseudo")]
+        if self.pseudo {
+            if let Some(transform) = bundle
+                .locales
+                .first()
+                .and_then(crate::pseudo::get_transform)
+            {
+                bundle.set_transform(Some(transform));
+            }
+        }
+
+        for res in self.resources {
+            if let Err(err) = bundle.add_resource(res) {
+                errors.extend(err);
+            }
+        }
+        bundle
+    }
+}
@@ -73,14 +73,78 @@
 //! the `fluent-bundle` crate directly, while the ecosystem
 //! matures and higher level APIs are being developed.
 //!
+//! # Optional Features
+//!
+//! The higher level crates of the ecosystem can be enabled via Cargo features
+//! and are re-exported from this crate:
+//!
+//! * `fluent-fallback` - re-exported as [`fallback`], provides `Localization`
+//!   with locale fallbacking.
+//! * `fluent-resmgr` - re-exported as [`resmgr`], provides a file system based
+//!   `ResourceManager`.
+//! * `fluent-pseudo` - exposed as [`pseudo`], provides pseudolocalization
+//!   which [`FluentBundleBuilder`] applies to pseudolocales such as `en-XA`.
+//!
+//! The most commonly used items are available via the [`prelude`].
+//!
 //! [`FluentBundle`]: ./struct.FluentBundle.html
 //! [`FluentResource`]: ./struct.FluentResource.html
 //! [`FluentMessage`]: ./struct.FluentMessage.html
 //! [`FluentArgs`]: ./type.FluentArgs.html
 //! [`FluentValue`]: ./struct.FluentValue.html
+//! [`fallback`]: ./fallback/index.html
+//! [`resmgr`]: ./resmgr/index.html
+//! [`pseudo`]: ./pseudo/index.html
+
+mod builder;
+#[cfg(feature = "fluent-pseudo")]
+pub mod pseudo;
 
+pub use builder::FluentBundleBuilder;
 pub use fluent_bundle::*;
 
+#[cfg(feature = "fluent-fallback")]
+pub use fluent_fallback as fallback;
+#[cfg(feature = "fluent-resmgr")]
+pub use fluent_resmgr as resmgr;
+
+/// A collection of the most commonly used items.
+///
+/// # Example
+///
+/// ```
+/// use fluent::prelude::*;
+/// use unic_langid::langid;
+///
+/// let res = FluentResource::try_new("intro = Welcome, { $name }.".to_string())
+///     .expect("Failed to parse an FTL string.");
+/// let mut errors = vec![];
+/// let bundle = FluentBundleBuilder::new(vec![langid!("en-US")])
+///     .add_resource(res)
+///     .use_isolating(false)
+///     .build(&mut errors);
+///
+/// let args = fluent_args!["name" => "John"];
+/// let msg = bundle.get_message("intro").expect("Message doesn't exist.");
+/// let value = bundle.format_pattern(msg.value().unwrap(), Some(&args), &mut errors);
+/// assert_eq!(value, "Welcome, John.");
+/// ```
+pub mod prelude {
+    pub use crate::{
+        fluent_args, FluentArgs, FluentBundle, FluentBundleBuilder, FluentError, FluentMessage,
+        FluentResource, FluentValue,
+    };
+
+    #[cfg(feature = "fluent-fallback")]
+    pub use fluent_fallback::{
+        types::{L10nKey, ResourceId, ResourceType, ToResourceId},
+        Localization, LocalizationError,
+    };
+
+    #[cfg(feature = "fluent-resmgr")]
+    pub use fluent_resmgr::ResourceManager;
+}
+
 /// A helper macro to simplify creation of FluentArgs.
 ///
 /// # Example
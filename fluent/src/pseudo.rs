@@ -0,0 +1,51 @@
+//! Pseudolocalization helpers built on top of [`fluent_pseudo`].
+//!
+//! Pseudolocales are regular locale identifiers with a private-use region
+//! subtag, which lets them flow through language negotiation like any
+//! other locale:
+//!
+//! * `XA` (for example `en-XA`) - accented and elongated text, useful for
+//!   spotting hardcoded strings and truncated layouts.
+//! * `XB` (for example `ar-XB`) - flipped, right-to-left looking text, useful
+//!   for testing bidirectional layouts.
+use std::borrow::Cow;
+
+use unic_langid::LanguageIdentifier;
+
+pub use fluent_pseudo::{transform, transform_dom};
+
+/// Accented and elongated pseudolocalization used for `XA` pseudolocales.
+pub fn transform_accented(s: &str) -> Cow<'_, str> {
+    transform(s, false, true)
+}
+
+/// Flipped pseudolocalization used for `XB` pseudolocales.
+pub fn transform_bidi(s: &str) -> Cow<'_, str> {
+    transform(s, true, false)
+}
+
+/// Returns the transform that should be used for a given locale,
+/// or `None` if the locale is not a pseudolocale.
+///
+/// # Example
+///
+/// ```
+/// use fluent::pseudo::get_transform;
+/// use unic_langid::langid;
+///
+/// assert!(get_transform(&langid!("en-XA")).is_some());
+/// assert!(get_transform(&langid!("ar-XB")).is_some());
+/// assert!(get_transform(&langid!("en-US")).is_none());
+/// ```
+pub fn get_transform(locale: &LanguageIdentifier) -> Option<fn(&str) -> Cow<str>> {
+    match locale.region.as_ref().map(|region| region.as_str()) {
+        Some("XA") => Some(transform_accented),
+        Some("XB") => Some(transform_bidi),
+        _ => None,
+    }
+}
+
+/// Returns `true` if the locale is one of the supported pseudolocales.
+pub fn is_pseudo_locale(locale: &LanguageIdentifier) -> bool {
+    get_transform(locale).is_some()
+}
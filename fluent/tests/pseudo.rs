@@ -32,3 +32,30 @@ fn test_pseudo() {
         assert_eq!(val, "Ħeeŀŀoo Ẇoořŀḓ");
     }
 }
+
+#[cfg(feature = "fluent-pseudo")]
+#[test]
+fn test_builder_pseudo_locales() {
+    use fluent::{FluentBundleBuilder, FluentResource};
+    use unic_langid::langid;
+
+    let get_value = |locale, pseudo| {
+        let res = FluentResource::try_new(String::from("key = Hello World")).unwrap();
+        let mut errors = vec![];
+        let bundle = FluentBundleBuilder::new(vec![locale])
+            .add_resource(res)
+            .pseudolocalize(pseudo)
+            .build(&mut errors);
+        assert!(errors.is_empty());
+
+        let msg = bundle.get_message("key").unwrap();
+        bundle
+            .format_pattern(msg.value().unwrap(), None, &mut errors)
+            .to_string()
+    };
+
+    assert_eq!(get_value(langid!("en-US"), true), "Hello World");
+    assert_eq!(get_value(langid!("en-XA"), true), "Ħeeŀŀoo Ẇoořŀḓ");
+    assert_eq!(get_value(langid!("ar-XB"), true), "Hǝʅʅo Moɹʅp");
+    assert_eq!(get_value(langid!("en-XA"), false), "Hello World");
+}
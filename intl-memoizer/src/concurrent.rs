@@ -1,17 +1,22 @@
 use super::*;
-use std::sync::Mutex;
+use std::sync::RwLock;
 
+/// A thread-safe counterpart of [`IntlLangMemoizer`](super::IntlLangMemoizer).
+///
+/// Lookups of already constructed formatters only take a shared read lock,
+/// so multiple threads can use the memoizer at the same time. An exclusive
+/// lock is only taken when a formatter has to be constructed.
 #[derive(Debug)]
 pub struct IntlLangMemoizer {
     lang: LanguageIdentifier,
-    map: Mutex<type_map::concurrent::TypeMap>,
+    map: RwLock<type_map::concurrent::TypeMap>,
 }
 
 impl IntlLangMemoizer {
     pub fn new(lang: LanguageIdentifier) -> Self {
         Self {
             lang,
-            map: Mutex::new(type_map::concurrent::TypeMap::new()),
+            map: RwLock::new(type_map::concurrent::TypeMap::new()),
         }
     }
 
@@ -22,7 +27,17 @@ impl IntlLangMemoizer {
         I::Args: Send + Sync + 'static,
         U: FnOnce(&I) -> R,
     {
-        let mut map = self.map.lock().unwrap();
+        {
+            let map = self.map.read().unwrap();
+            if let Some(e) = map
+                .get::<HashMap<I::Args, I>>()
+                .and_then(|cache| cache.get(&args))
+            {
+                return Ok(cb(e));
+            }
+        }
+
+        let mut map = self.map.write().unwrap();
         let cache = map
             .entry::<HashMap<I::Args, I>>()
             .or_insert_with(HashMap::new);